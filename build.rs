@@ -0,0 +1,30 @@
+use std::env;
+use std::process::Command;
+
+// Exposes the toolchain the crate is compiled with. Dependencies are built with the same compiler, target and profile as the
+// application, so these describe the application binary too.
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-env-changed=RUSTC");
+
+    let rustc = env::var("RUSTC").unwrap_or_else(|_| "rustc".into());
+
+    if let Some(version) = Command::new(rustc)
+        .arg("--version")
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+    {
+        println!("cargo:rustc-env=DONTPANIC_RUSTC_VERSION={}", version.trim());
+    }
+
+    for (var, name) in [
+        ("TARGET", "DONTPANIC_TARGET"),
+        ("PROFILE", "DONTPANIC_PROFILE"),
+    ] {
+        if let Ok(value) = env::var(var) {
+            println!("cargo:rustc-env={}={}", name, value);
+        }
+    }
+}
@@ -0,0 +1,34 @@
+/// Replaces redacted argument values.
+const REDACTED: &str = "[redacted]";
+
+/// Command line arguments of the process, with the values of `redacted_flags` replaced, see [`Builder::redact_args`](crate::Builder::redact_args).
+///
+/// Values are redacted both when passed as the next argument, `--token abc`, and inline, `--token=abc`.
+pub fn capture(redacted_flags: &[String]) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut redact_next = false;
+
+    for arg in std::env::args_os() {
+        let arg = arg.to_string_lossy().into_owned();
+
+        if redact_next {
+            args.push(REDACTED.to_string());
+            redact_next = false;
+            continue;
+        }
+
+        let inline = redacted_flags.iter().find(|flag| {
+            arg.strip_prefix(flag.as_str())
+                .is_some_and(|rest| rest.starts_with('='))
+        });
+
+        if let Some(flag) = inline {
+            args.push(format!("{}={}", flag, REDACTED));
+        } else {
+            redact_next = redacted_flags.contains(&arg);
+            args.push(arg);
+        }
+    }
+
+    args
+}
@@ -0,0 +1,161 @@
+use std::backtrace::Backtrace;
+
+use super::BacktraceMode;
+#[cfg(feature = "frames")]
+use super::StackFrame;
+
+/// Symbol prefixes of frames belonging to the standard library or dontpanic itself.
+const INTERNAL_PREFIXES: &[&str] = &[
+    "std::",
+    "core::",
+    "alloc::",
+    "dontpanic::",
+    "<alloc::",
+    "<core::",
+    "<std::",
+    "rust_begin_unwind",
+    "__rust",
+];
+
+/// Captures a backtrace of the current thread according to `mode`. With `trim` set, leading frames from the
+/// runtime and dontpanic are dropped so the backtrace starts at the frame that triggered the report.
+pub fn capture(mode: BacktraceMode, trim: bool) -> Option<String> {
+    let backtrace = match mode {
+        BacktraceMode::Full => Backtrace::force_capture().to_string(),
+        BacktraceMode::Short => return Some(shorten(&Backtrace::force_capture().to_string())),
+        BacktraceMode::Disabled => return None,
+    };
+
+    if trim {
+        Some(trim_leading(&backtrace))
+    } else {
+        Some(backtrace)
+    }
+}
+
+/// Captures the frames of the current thread's stack according to `mode` and `trim`, just like [`capture`].
+///
+/// Frames inlined into another are listed separately. Function names, files and lines are left empty when debug info is missing.
+#[cfg(feature = "frames")]
+pub fn capture_frames(mode: BacktraceMode, trim: bool) -> Vec<StackFrame> {
+    if mode == BacktraceMode::Disabled {
+        return Vec::new();
+    }
+
+    let mut frames = Vec::new();
+
+    ::backtrace::trace(|frame| {
+        let mut symbols = Vec::new();
+
+        ::backtrace::resolve_frame(frame, |symbol| {
+            symbols.push(StackFrame {
+                function: symbol.name().map(|name| format!("{:#}", name)),
+                file: symbol.filename().map(|file| file.display().to_string()),
+                line: symbol.lineno(),
+                col: symbol.colno(),
+            });
+        });
+
+        // Frames without any symbol information are still listed, so the depth of the stack is preserved
+        if symbols.is_empty() {
+            symbols.push(StackFrame {
+                function: None,
+                file: None,
+                line: None,
+                col: None,
+            });
+        }
+
+        frames.extend(symbols);
+        true
+    });
+
+    let function = |frame: &StackFrame| frame.function.clone().unwrap_or_default();
+    // Unlike std's backtrace, the trace includes the frames of the backtrace crate capturing it
+    let is_internal = |function: &str| function.starts_with("backtrace::") || is_internal(function);
+
+    match mode {
+        BacktraceMode::Short => frames
+            .into_iter()
+            .take_while(|frame| !function(frame).contains("__rust_begin_short_backtrace"))
+            .filter(|frame| !is_internal(&function(frame)))
+            .collect(),
+        _ if trim => frames
+            .into_iter()
+            .skip_while(|frame| frame.function.is_none() || is_internal(&function(frame)))
+            .collect(),
+        _ => frames,
+    }
+}
+
+/// Removes frames from the std runtime, panic machinery and dontpanic, and everything from the runtime's
+/// entry point onwards.
+fn shorten(backtrace: &str) -> String {
+    let mut short = String::new();
+
+    for frame in frames(backtrace) {
+        let symbol = symbol(&frame);
+
+        if symbol.contains("__rust_begin_short_backtrace") {
+            break;
+        }
+
+        if is_internal(symbol) {
+            continue;
+        }
+
+        short.push_str(&frame);
+        short.push('\n');
+    }
+
+    short
+}
+
+/// Removes internal frames at the top of the backtrace, keeping everything from the first user frame onwards.
+fn trim_leading(backtrace: &str) -> String {
+    let mut trimmed = String::new();
+
+    for frame in frames(backtrace).skip_while(|frame| is_internal(symbol(frame))) {
+        trimmed.push_str(&frame);
+        trimmed.push('\n');
+    }
+
+    trimmed
+}
+
+/// Splits the output of [`Backtrace`]'s `Display` impl into frames, each holding the symbol line and its location lines.
+fn frames(backtrace: &str) -> impl Iterator<Item = String> {
+    let mut frames: Vec<String> = vec![];
+
+    for line in backtrace.lines() {
+        let is_frame_start = line
+            .trim_start()
+            .split_once(": ")
+            .is_some_and(|(idx, _)| idx.parse::<usize>().is_ok());
+
+        match frames.last_mut() {
+            Some(frame) if !is_frame_start => {
+                frame.push('\n');
+                frame.push_str(line);
+            }
+            _ => frames.push(line.to_string()),
+        }
+    }
+
+    frames.into_iter()
+}
+
+fn symbol(frame: &str) -> &str {
+    frame
+        .lines()
+        .next()
+        .and_then(|line| line.split_once(": "))
+        .map(|(_, symbol)| symbol)
+        .unwrap_or_default()
+}
+
+fn is_internal(symbol: &str) -> bool {
+    INTERNAL_PREFIXES
+        .iter()
+        .any(|prefix| symbol.starts_with(prefix))
+}
@@ -0,0 +1,42 @@
+/// Build metadata passed to [`Builder::build_info`](crate::Builder::build_info), usually created with [`build_info!`](crate::build_info).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct BuildInfo {
+    /// Application version
+    pub version: Option<&'static str>,
+    /// Git commit sha the application was built from
+    pub commit: Option<&'static str>,
+    /// Time the application was built at, in any format
+    pub built_at: Option<&'static str>,
+}
+
+/// Creates a [`BuildInfo`] from variables set at compile time of the crate invoking the macro.
+///
+/// - `version` is read from `CARGO_PKG_VERSION`
+/// - `commit` is read from `VERGEN_GIT_SHA`
+/// - `built_at` is read from `VERGEN_BUILD_TIMESTAMP`
+///
+/// The `VERGEN_*` variables are emitted by a [vergen](https://docs.rs/vergen/latest/vergen/) build script with the git and build
+/// instructions enabled. Missing variables leave the corresponding field empty.
+///
+/// ```no_run
+/// use anyhow::Result;
+///
+/// fn main() -> Result<()> {
+///     dontpanic::builder("<PROJECT_API_KEY>")
+///         .build_info(dontpanic::build_info!())
+///         .build()?;
+///
+///     Ok(())
+/// }
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "build-info")))]
+#[macro_export]
+macro_rules! build_info {
+    () => {
+        $crate::BuildInfo {
+            version: ::core::option_env!("CARGO_PKG_VERSION"),
+            commit: ::core::option_env!("VERGEN_GIT_SHA"),
+            built_at: ::core::option_env!("VERGEN_BUILD_TIMESTAMP"),
+        }
+    };
+}
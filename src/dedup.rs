@@ -0,0 +1,91 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::VecDeque;
+use std::hash::{Hash, Hasher};
+use std::sync::{Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+use super::ReportLocation;
+
+/// Number of distinct reports remembered for deduplication.
+const CAPACITY: usize = 64;
+
+/// Suppresses identical reports sent within a time window.
+#[derive(Debug)]
+pub struct Dedup {
+    window: Duration,
+    recent: Mutex<VecDeque<Entry>>,
+}
+
+#[derive(Debug)]
+struct Entry {
+    hash: u64,
+    sent_at: Instant,
+    suppressed: u64,
+}
+
+impl Dedup {
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            recent: Mutex::new(VecDeque::with_capacity(CAPACITY)),
+        }
+    }
+
+    /// Returns `None` if an identical report was sent within the window. Otherwise returns the number of
+    /// identical reports suppressed since the last one was sent.
+    pub fn check(&self, title: &str, loc: Option<&ReportLocation>) -> Option<u64> {
+        let mut hasher = DefaultHasher::new();
+        title.hash(&mut hasher);
+
+        if let Some(loc) = loc {
+            loc.file.hash(&mut hasher);
+            loc.line.hash(&mut hasher);
+            loc.col.hash(&mut hasher);
+        }
+
+        self.check_hash(hasher.finish())
+    }
+
+    /// Same as [`check`](Dedup::check), but reports are considered identical when their file and line match, regardless of the title.
+    pub fn check_location(&self, loc: &ReportLocation) -> Option<u64> {
+        let mut hasher = DefaultHasher::new();
+        loc.file.hash(&mut hasher);
+        loc.line.hash(&mut hasher);
+
+        self.check_hash(hasher.finish())
+    }
+
+    fn check_hash(&self, hash: u64) -> Option<u64> {
+        let now = Instant::now();
+
+        let mut recent = self.recent.lock().unwrap_or_else(PoisonError::into_inner);
+
+        let Some(idx) = recent.iter().position(|entry| entry.hash == hash) else {
+            if recent.len() == CAPACITY {
+                recent.pop_back();
+            }
+
+            recent.push_front(Entry {
+                hash,
+                sent_at: now,
+                suppressed: 0,
+            });
+
+            return Some(0);
+        };
+
+        let mut entry = recent.remove(idx)?;
+
+        let res = if now.duration_since(entry.sent_at) < self.window {
+            entry.suppressed += 1;
+            None
+        } else {
+            entry.sent_at = now;
+            Some(std::mem::take(&mut entry.suppressed))
+        };
+
+        recent.push_front(entry);
+
+        res
+    }
+}
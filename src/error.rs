@@ -8,6 +8,24 @@ use log::SetLoggerError;
 pub enum Error {
     /// An empty API Key was provided to [`builder`](crate::builder)
     EmptyApiKey,
+    /// A log buffer size of zero was provided to [`log_buffer_size`](crate::Builder::log_buffer_size)
+    InvalidLogBufferSize,
+    /// A header with a malformed name or value was provided to [`header`](crate::Builder::header). Contains the header name.
+    InvalidHeader(String),
+    /// The url provided to [`proxy`](crate::Builder::proxy) could not be parsed. Contains the parsing error.
+    InvalidProxy(String),
+    /// The certificate or private key provided to [`client_certificate`](crate::Builder::client_certificate) are invalid. Contains the error description.
+    #[cfg_attr(docsrs, doc(cfg(feature = "mtls")))]
+    #[cfg(feature = "mtls")]
+    Tls(String),
+    /// The backend server could not be reached by [`ping`](crate::Client::ping) or [`validate_on_build`](crate::Builder::validate_on_build).
+    /// Contains the error description.
+    BackendUnreachable(String),
+    /// The backend server rejected the API Key provided to [`builder`](crate::builder), checked by [`ping`](crate::Client::ping) or
+    /// [`validate_on_build`](crate::Builder::validate_on_build).
+    InvalidApiKey,
+    /// Error returned by [`install_global`](crate::Client::install_global) if a client has already been installed.
+    GlobalClientAlreadySet,
     /// Error returned by [`set_logger`](crate::Client::set_logger) if another logger has already been set.
     #[cfg_attr(docsrs, doc(cfg(feature = "log")))]
     #[cfg(feature = "log")]
@@ -20,12 +38,106 @@ impl Display for Error {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::EmptyApiKey => write!(f, "API Key cannot be empty"),
+            Self::InvalidLogBufferSize => write!(f, "Log buffer size cannot be zero"),
+            Self::InvalidHeader(name) => write!(f, "Invalid header: {:?}", name),
+            Self::InvalidProxy(e) => write!(f, "Invalid proxy: {}", e),
+            #[cfg(feature = "mtls")]
+            Self::Tls(e) => write!(f, "TLS error: {}", e),
+            Self::BackendUnreachable(e) => write!(f, "Backend server unreachable: {}", e),
+            Self::InvalidApiKey => write!(f, "API Key rejected by the backend server"),
+            Self::GlobalClientAlreadySet => write!(f, "A global client has already been installed"),
             #[cfg(feature = "log")]
             Self::SetLoggerError(e) => write!(f, "{}", e),
         }
     }
 }
 
+/// The Errors that may occur when sending a report to the backend server. Passed to [`on_send_error`](crate::Builder::on_send_error) callbacks.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum SendError {
+    /// The report could not be serialized or compressed
+    Serialization(std::io::Error),
+    /// The server responded with an error status code
+    Status {
+        /// Url the report was sent to
+        url: String,
+        /// HTTP status code
+        code: u16,
+        /// Response body, if it could be read
+        response: Option<String>,
+    },
+    /// The request failed before a response was received, e.g. the server is unreachable or the request timed out
+    Transport {
+        /// Url the report was sent to
+        url: String,
+        /// Description of the error
+        message: String,
+    },
+}
+
+impl SendError {
+    /// Transport errors and 5xx responses may succeed when sent again later
+    pub(crate) fn is_retryable(&self) -> bool {
+        match self {
+            Self::Serialization(_) => false,
+            Self::Status { code, .. } => *code >= 500,
+            Self::Transport { .. } => true,
+        }
+    }
+}
+
+impl std::error::Error for SendError {}
+
+impl Display for SendError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Serialization(e) => write!(f, "Error encoding report. Error: {}", e),
+            Self::Status {
+                url,
+                code,
+                response,
+            } => write!(
+                f,
+                "Error sending report to {}. Code: {}, Response: {:?}",
+                url, code, response
+            ),
+            Self::Transport { url, message } => write!(
+                f,
+                "Transport error sending report to {}. Error: {}",
+                url, message
+            ),
+        }
+    }
+}
+
+/// The Errors a [`Transport`](crate::Transport) may return when sending a report.
+#[derive(Debug)]
+pub enum TransportError {
+    /// The server responded with an error status code. Reports are retried on 5xx responses.
+    Status {
+        /// HTTP status code
+        code: u16,
+        /// Response body, if it could be read
+        response: Option<String>,
+    },
+    /// The request failed before a response was received. Reports are retried on these errors.
+    Other(String),
+}
+
+impl std::error::Error for TransportError {}
+
+impl Display for TransportError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Status { code, response } => {
+                write!(f, "Code: {}, Response: {:?}", code, response)
+            }
+            Self::Other(message) => write!(f, "{}", message),
+        }
+    }
+}
+
 #[cfg(feature = "log")]
 impl From<SetLoggerError> for Error {
     fn from(value: SetLoggerError) -> Self {
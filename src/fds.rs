@@ -0,0 +1,40 @@
+/// Number of file descriptors open in the current process, or `None` on platforms where it can't be determined.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub fn open_fds() -> Option<u64> {
+    let entries = std::fs::read_dir("/proc/self/fd").ok()?;
+
+    // Reading the directory takes a file descriptor itself, which is listed as well
+    Some((entries.count() as u64).saturating_sub(1))
+}
+
+/// Number of file descriptors open in the current process, or `None` on platforms where it can't be determined.
+#[cfg(target_os = "macos")]
+pub fn open_fds() -> Option<u64> {
+    let pid = std::process::id() as libc::c_int;
+
+    // SAFETY: with a null buffer, proc_pidinfo only returns the buffer size needed
+    let size =
+        unsafe { libc::proc_pidinfo(pid, libc::PROC_PIDLISTFDS, 0, std::ptr::null_mut(), 0) };
+
+    if size <= 0 {
+        return None;
+    }
+
+    let mut buf = vec![0u8; size as usize];
+
+    // SAFETY: the buffer is valid for writes of `size` bytes
+    let written =
+        unsafe { libc::proc_pidinfo(pid, libc::PROC_PIDLISTFDS, 0, buf.as_mut_ptr().cast(), size) };
+
+    if written <= 0 {
+        return None;
+    }
+
+    Some((written / libc::PROC_PIDLISTFD_SIZE) as u64)
+}
+
+/// Number of file descriptors open in the current process, or `None` on platforms where it can't be determined.
+#[cfg(not(any(target_os = "linux", target_os = "android", target_os = "macos")))]
+pub fn open_fds() -> Option<u64> {
+    None
+}
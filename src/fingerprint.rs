@@ -0,0 +1,69 @@
+use super::ReportData;
+
+/// Default fingerprint, the report title with numbers and hex sequences like addresses stripped out. The file and line a panic
+/// title ends with are kept, so different bugs with the same message don't end up in the same issue.
+///
+/// `index out of bounds: the len is 3 but the index is 7 in src/main.rs:4:5` becomes
+/// `index out of bounds: the len is  but the index is  in src/main.rs:4`.
+pub fn default(data: &ReportData) -> String {
+    let (message, location) = match split_location(data) {
+        Some((message, location)) => (message, Some(location)),
+        None => (data.title.as_str(), None),
+    };
+
+    let mut fingerprint = normalize(message);
+
+    if let Some(location) = location {
+        fingerprint.push_str(&location);
+    }
+
+    fingerprint
+}
+
+/// Splits a title ending with the report location, like panic titles do, into the message and ` in {file}:{line}`.
+fn split_location(data: &ReportData) -> Option<(&str, String)> {
+    let location = data.location.as_ref()?;
+    let file_line = format!(" in {}:{}", location.file, location.line);
+
+    let suffix = match location.col {
+        Some(col) => format!("{file_line}:{col}"),
+        None => file_line.clone(),
+    };
+
+    let message = data.title.strip_suffix(&suffix)?;
+
+    Some((message, file_line))
+}
+
+fn normalize(text: &str) -> String {
+    let mut fingerprint = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find(|c: char| c.is_ascii_alphanumeric()) {
+        fingerprint.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let end = rest
+            .find(|c: char| !c.is_ascii_alphanumeric())
+            .unwrap_or(rest.len());
+
+        let word = &rest[..end];
+        rest = &rest[end..];
+
+        if !is_hex(word) {
+            fingerprint.extend(word.chars().filter(|c| !c.is_ascii_digit()));
+        }
+    }
+
+    fingerprint.push_str(rest);
+    fingerprint
+}
+
+/// `0x` prefixed hex literals, and words made of hex digits only that contain at least one decimal digit.
+fn is_hex(word: &str) -> bool {
+    if let Some(digits) = word.strip_prefix("0x").or_else(|| word.strip_prefix("0X")) {
+        return !digits.is_empty() && digits.chars().all(|c| c.is_ascii_hexdigit());
+    }
+
+    word.chars().all(|c| c.is_ascii_hexdigit()) && word.chars().any(|c| c.is_ascii_digit())
+}
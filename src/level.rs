@@ -0,0 +1,95 @@
+/// A severity threshold for log and tracing events.
+///
+/// Events at the given level and above (more severe) pass the filter. Can be created from the
+/// [log](https://docs.rs/log/latest/log/enum.LevelFilter.html) and [tracing](https://docs.rs/tracing/latest/tracing/level_filters/struct.LevelFilter.html) equivalents.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum LevelFilter {
+    /// Disables all events.
+    Off,
+    /// Only error events.
+    Error,
+    /// Warnings and errors.
+    Warn,
+    /// Info, warnings and errors.
+    Info,
+    /// Everything except trace events.
+    Debug,
+    /// All events.
+    Trace,
+}
+
+/// Severity of a log message or tracing event, as sent in the `lvl` field of [`LogEntry`](crate::LogEntry).
+///
+/// This is the only place the wire encoding is defined, both the log and tracing integrations convert their levels through it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[repr(u8)]
+pub(crate) enum Severity {
+    Error = 1,
+    Warn = 2,
+    Info = 3,
+    Debug = 4,
+    Trace = 5,
+}
+
+#[cfg(feature = "log")]
+impl From<log::Level> for Severity {
+    fn from(value: log::Level) -> Self {
+        match value {
+            log::Level::Error => Self::Error,
+            log::Level::Warn => Self::Warn,
+            log::Level::Info => Self::Info,
+            log::Level::Debug => Self::Debug,
+            log::Level::Trace => Self::Trace,
+        }
+    }
+}
+
+#[cfg(feature = "tracing")]
+impl From<tracing::Level> for Severity {
+    fn from(value: tracing::Level) -> Self {
+        match value {
+            tracing::Level::ERROR => Self::Error,
+            tracing::Level::WARN => Self::Warn,
+            tracing::Level::INFO => Self::Info,
+            tracing::Level::DEBUG => Self::Debug,
+            tracing::Level::TRACE => Self::Trace,
+        }
+    }
+}
+
+impl LevelFilter {
+    /// Checks a level encoded as a [`Severity`] against this filter.
+    pub(crate) fn allows(self, level: u8) -> bool {
+        level <= self as u8
+    }
+}
+
+#[cfg(feature = "log")]
+impl From<log::LevelFilter> for LevelFilter {
+    fn from(value: log::LevelFilter) -> Self {
+        match value {
+            log::LevelFilter::Off => Self::Off,
+            log::LevelFilter::Error => Self::Error,
+            log::LevelFilter::Warn => Self::Warn,
+            log::LevelFilter::Info => Self::Info,
+            log::LevelFilter::Debug => Self::Debug,
+            log::LevelFilter::Trace => Self::Trace,
+        }
+    }
+}
+
+#[cfg(feature = "tracing")]
+impl From<tracing::level_filters::LevelFilter> for LevelFilter {
+    fn from(value: tracing::level_filters::LevelFilter) -> Self {
+        use tracing::level_filters::LevelFilter as Filter;
+
+        match value {
+            Filter::OFF => Self::Off,
+            Filter::ERROR => Self::Error,
+            Filter::WARN => Self::Warn,
+            Filter::INFO => Self::Info,
+            Filter::DEBUG => Self::Debug,
+            _ => Self::Trace,
+        }
+    }
+}
@@ -0,0 +1,133 @@
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicUsize, Ordering};
+#[cfg(any(feature = "log", feature = "tracing"))]
+use std::sync::Arc;
+use std::sync::{Mutex, PoisonError};
+
+use super::LogEntry;
+
+/// Log messages waiting to be sent with the next report, bounded by both their count and their total size.
+///
+/// The oldest messages are evicted first, once either [`Builder::log_buffer_size`](crate::Builder::log_buffer_size) or
+/// [`Builder::log_buffer_bytes`](crate::Builder::log_buffer_bytes) is exceeded.
+#[derive(Debug)]
+pub struct LogBuffer {
+    // Without the log or tracing features nothing is ever pushed, so the limits aren't needed
+    #[cfg_attr(not(any(feature = "log", feature = "tracing")), allow(dead_code))]
+    max_count: usize,
+    #[cfg_attr(not(any(feature = "log", feature = "tracing")), allow(dead_code))]
+    max_bytes: Option<usize>,
+    inner: Mutex<Inner>,
+    /// Number of live [`LogSender`]s
+    senders: AtomicUsize,
+}
+
+#[derive(Debug, Default)]
+struct Inner {
+    entries: VecDeque<LogEntry>,
+    bytes: usize,
+}
+
+impl LogBuffer {
+    pub fn new(max_count: usize, max_bytes: Option<usize>) -> Self {
+        Self {
+            max_count,
+            max_bytes,
+            inner: Mutex::default(),
+            senders: AtomicUsize::new(0),
+        }
+    }
+
+    #[cfg(any(feature = "log", feature = "tracing"))]
+    pub fn push(&self, entry: LogEntry) {
+        let mut inner = self.inner.lock().unwrap_or_else(PoisonError::into_inner);
+
+        inner.bytes += entry_size(&entry);
+        inner.entries.push_back(entry);
+
+        while inner.entries.len() > self.max_count
+            || self
+                .max_bytes
+                .is_some_and(|max_bytes| inner.bytes > max_bytes)
+        {
+            let Some(evicted) = inner.entries.pop_front() else {
+                break;
+            };
+
+            inner.bytes -= entry_size(&evicted);
+        }
+    }
+
+    /// Removes and returns all buffered messages, oldest first.
+    pub fn drain(&self) -> Vec<LogEntry> {
+        let mut inner = self.inner.lock().unwrap_or_else(PoisonError::into_inner);
+        inner.bytes = 0;
+        inner.entries.drain(..).collect()
+    }
+
+    /// Returns all buffered messages, oldest first, keeping them in the buffer.
+    #[cfg(any(feature = "log", feature = "tracing"))]
+    pub fn entries(&self) -> Vec<LogEntry> {
+        let inner = self.inner.lock().unwrap_or_else(PoisonError::into_inner);
+        inner.entries.iter().cloned().collect()
+    }
+
+    /// Removes all buffered messages.
+    #[cfg(any(feature = "log", feature = "tracing"))]
+    pub fn clear(&self) {
+        let mut inner = self.inner.lock().unwrap_or_else(PoisonError::into_inner);
+        inner.bytes = 0;
+        inner.entries.clear();
+    }
+
+    /// Whether every [`LogSender`] was dropped, so no more messages will be captured.
+    pub fn is_disconnected(&self) -> bool {
+        self.senders.load(Ordering::Relaxed) == 0
+    }
+}
+
+/// Handle for storing messages in a [`LogBuffer`], held by the client and every logger or tracing layer created from it.
+#[cfg(any(feature = "log", feature = "tracing"))]
+#[derive(Debug)]
+pub struct LogSender {
+    buffer: Arc<LogBuffer>,
+}
+
+#[cfg(any(feature = "log", feature = "tracing"))]
+impl LogSender {
+    pub fn new(buffer: Arc<LogBuffer>) -> Self {
+        buffer.senders.fetch_add(1, Ordering::Relaxed);
+        Self { buffer }
+    }
+
+    pub fn send(&self, entry: LogEntry) {
+        self.buffer.push(entry);
+    }
+}
+
+#[cfg(any(feature = "log", feature = "tracing"))]
+impl Clone for LogSender {
+    fn clone(&self) -> Self {
+        Self::new(self.buffer.clone())
+    }
+}
+
+#[cfg(any(feature = "log", feature = "tracing"))]
+impl Drop for LogSender {
+    fn drop(&mut self) {
+        self.buffer.senders.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// Approximate memory used by a message, counting only its text.
+#[cfg(any(feature = "log", feature = "tracing"))]
+fn entry_size(entry: &LogEntry) -> usize {
+    entry.message.len()
+        + entry.module.as_ref().map_or(0, String::len)
+        + entry.file.as_ref().map_or(0, String::len)
+        + entry
+            .fields
+            .iter()
+            .map(|(name, value)| name.len() + value.len())
+            .sum::<usize>()
+}
@@ -0,0 +1,77 @@
+use std::cell::RefCell;
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+use super::log_buffer::LogBuffer;
+use super::LogEntry;
+
+thread_local! {
+    /// Snapshot entered on the current thread, receiving a copy of every captured log message.
+    static CURRENT: RefCell<Option<LogSnapshot>> = const { RefCell::new(None) };
+}
+
+/// A separate log buffer for a unit of work, like a single request in a server. Created with [`Client::log_snapshot`](crate::Client::log_snapshot).
+///
+/// While [entered](LogSnapshot::enter) on a thread, captured log messages from that thread are copied into the snapshot, in addition to the
+/// shared log buffer. Reports sent with [`Client::report_error_with_logs`](crate::Client::report_error_with_logs) then include only these
+/// messages, so concurrent requests don't end up in each other's reports. Like the shared buffer, a snapshot is bounded by
+/// [`log_buffer_size`](crate::Builder::log_buffer_size) and [`log_buffer_bytes`](crate::Builder::log_buffer_bytes).
+///
+/// Cloning a snapshot is cheap, clones share the same buffer.
+#[derive(Clone)]
+pub struct LogSnapshot {
+    buffer: Arc<LogBuffer>,
+}
+
+impl LogSnapshot {
+    pub(crate) fn new(max_count: usize, max_bytes: Option<usize>) -> Self {
+        Self {
+            buffer: Arc::new(LogBuffer::new(max_count, max_bytes)),
+        }
+    }
+
+    /// Capture log messages from the current thread into this snapshot until the returned guard is dropped. Dropping the guard
+    /// restores the snapshot entered before, if any.
+    ///
+    /// The guard is tied to the current thread. In async code, enter the snapshot around synchronous sections only, since the task
+    /// may continue on another thread after an `.await`.
+    #[must_use = "log messages are only captured into the snapshot while the guard is alive"]
+    pub fn enter(&self) -> LogSnapshotGuard {
+        let previous = CURRENT.replace(Some(self.clone()));
+
+        LogSnapshotGuard {
+            previous,
+            _not_send: PhantomData,
+        }
+    }
+
+    /// Messages captured so far, oldest first.
+    pub fn entries(&self) -> Vec<LogEntry> {
+        self.buffer.entries()
+    }
+}
+
+/// Keeps a [`LogSnapshot`] entered on the current thread, returned by [`LogSnapshot::enter`].
+pub struct LogSnapshotGuard {
+    previous: Option<LogSnapshot>,
+    _not_send: PhantomData<*const ()>,
+}
+
+impl Drop for LogSnapshotGuard {
+    fn drop(&mut self) {
+        let previous = self.previous.take();
+        let _ = CURRENT.try_with(|current| current.replace(previous));
+    }
+}
+
+/// Copies a captured message into the snapshot entered on the current thread, if any.
+pub(crate) fn record(entry: &LogEntry) {
+    // Already borrowed if a log message is emitted while a snapshot is dropped, there's nothing to record into then
+    let _ = CURRENT.try_with(|current| {
+        if let Ok(current) = current.try_borrow() {
+            if let Some(snapshot) = current.as_ref() {
+                snapshot.buffer.push(entry.clone());
+            }
+        }
+    });
+}
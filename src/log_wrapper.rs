@@ -1,33 +1,59 @@
-use std::{
-    sync::atomic::Ordering,
-    time::{SystemTime, UNIX_EPOCH},
-};
+use std::{collections::HashMap, sync::Arc};
 
-use log::{Level, Log, Metadata, Record};
-use ring_channel::{RingReceiver, RingSender};
+#[cfg(feature = "log-kv")]
+use log::kv::{self, Key, Value, VisitSource};
+use log::{Log, Metadata, Record};
 
-use super::{send_report, Config, LogEvent, ReportLocation};
+use super::level::Severity;
+use super::log_buffer::{LogBuffer, LogSender};
+use super::log_snapshot;
+use super::report::now_millis;
+use super::{create_sampled_report, worker, Config, LogEntry, Logs, ReportLocation, SENDING};
 
-impl From<&Record<'_>> for LogEvent {
+impl From<&Record<'_>> for LogEntry {
     fn from(record: &Record) -> Self {
+        let timestamp = now_millis();
+
         Self {
-            timestamp: SystemTime::now()
-                .duration_since(UNIX_EPOCH)
-                .map(|d| d.as_secs())
-                .unwrap_or_default(),
-            level: record.level() as u8,
+            timestamp: timestamp.unwrap_or_default(),
+            clock_skew: timestamp.is_none(),
+            level: Severity::from(record.level()) as u8,
             message: format!("{}", record.args()),
             module: record.module_path().map(String::from),
             file: record.file().map(String::from),
             line: record.line(),
+            fields: record_fields(record),
         }
     }
 }
 
+/// Structured key-values of a record, see the `kv` feature of the log crate.
+#[cfg(feature = "log-kv")]
+fn record_fields(record: &Record) -> HashMap<String, String> {
+    struct FieldVisitor<'a>(&'a mut HashMap<String, String>);
+
+    impl<'kvs> VisitSource<'kvs> for FieldVisitor<'_> {
+        fn visit_pair(&mut self, key: Key<'kvs>, value: Value<'kvs>) -> Result<(), kv::Error> {
+            self.0.insert(key.to_string(), value.to_string());
+            Ok(())
+        }
+    }
+
+    let mut fields = HashMap::new();
+    let _ = record.key_values().visit(&mut FieldVisitor(&mut fields));
+    fields
+}
+
+/// Without the `log-kv` feature, records carry only their message.
+#[cfg(not(feature = "log-kv"))]
+fn record_fields(_record: &Record) -> HashMap<String, String> {
+    HashMap::new()
+}
+
 pub struct LogWrapper<T> {
     pub next: T,
-    pub tx: RingSender<LogEvent>,
-    pub rx: RingReceiver<LogEvent>,
+    pub tx: LogSender,
+    pub logs: Arc<LogBuffer>,
     pub config: Config,
 }
 
@@ -42,13 +68,27 @@ where
     fn log(&self, record: &Record) {
         self.next.log(record);
 
-        if !self.config.is_enabled.load(Ordering::Relaxed) {
+        if !self.config.reports_enabled() {
             return;
         }
 
-        let _ = self.tx.send(LogEvent::from(record));
+        let module = record.module_path().unwrap_or(record.target());
+
+        if self
+            .config
+            .captures(Severity::from(record.level()) as u8, module)
+        {
+            let entry = LogEntry::from(record);
+            log_snapshot::record(&entry);
+            self.tx.send(entry);
+        }
 
-        if record.level() == Level::Error && self.config.report_on_log_errors {
+        // Reporting an error logged while sending a report could trigger another one, indefinitely
+        if !SENDING.get()
+            && self
+                .config
+                .triggers_report(Severity::from(record.level()) as u8, record.target())
+        {
             let title = format!("{}", record.args());
 
             let loc = if let (Some(file), Some(line)) = (record.file(), record.line()) {
@@ -61,7 +101,11 @@ where
                 None
             };
 
-            send_report(&self.config, title, loc, &self.rx)
+            if let Some(report) =
+                create_sampled_report(&self.config, title, loc, Logs::Buffer(&self.logs))
+            {
+                worker::enqueue(&self.config, report);
+            }
         }
     }
 
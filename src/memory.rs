@@ -0,0 +1,18 @@
+use sysinfo::{ProcessRefreshKind, ProcessesToUpdate, System};
+
+/// Resident memory of the current process in bytes, or `None` if it can't be read on this platform.
+pub fn rss_bytes() -> Option<u64> {
+    let pid = sysinfo::get_current_pid().ok()?;
+
+    let mut system = System::new();
+    system.refresh_processes_specifics(
+        ProcessesToUpdate::Some(&[pid]),
+        false,
+        ProcessRefreshKind::nothing().with_memory(),
+    );
+
+    system
+        .process(pid)
+        .map(|process| process.memory())
+        .filter(|&rss| rss > 0)
+}
@@ -0,0 +1,32 @@
+/// Events in the lifecycle of a report, passed to [`Builder::on_metric`](crate::Builder::on_metric) callbacks.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Metric {
+    /// A report was delivered to the backend server
+    ReportSent,
+    /// A report was discarded before being sent
+    ReportDropped(DropReason),
+    /// A report could not be delivered, after all retries
+    ReportFailed,
+    /// A report was dropped because the background queue was full, see [`Builder::queue_capacity`](crate::Builder::queue_capacity)
+    QueueFull,
+    /// A request was not sent because [`Builder::max_in_flight`](crate::Builder::max_in_flight) requests were already in flight
+    /// and none finished in time. The report is handled like any other failed delivery.
+    InFlightLimit,
+}
+
+/// Why a report was discarded, see [`Metric::ReportDropped`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum DropReason {
+    /// Over the limit set with [`Builder::max_reports_per_minute`](crate::Builder::max_reports_per_minute)
+    RateLimited,
+    /// Identical to a recent report, see [`Builder::dedup_window`](crate::Builder::dedup_window)
+    Deduplicated,
+    /// A panic at the same location was reported recently, see [`Builder::per_location_cooldown`](crate::Builder::per_location_cooldown)
+    LocationCooldown,
+    /// Not picked by [`Builder::sample_rate`](crate::Builder::sample_rate)
+    Sampled,
+    /// Cancelled by a [`Builder::before_send`](crate::Builder::before_send) callback
+    BeforeSend,
+}
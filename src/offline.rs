@@ -0,0 +1,98 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::thread;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde_json::{self, Value};
+
+use super::{try_send_report, Config};
+
+/// Distinguishes reports stored within the same nanosecond.
+static SEQ: AtomicU64 = AtomicU64::new(0);
+
+/// Writes a report that could not be delivered to the offline directory.
+pub fn store(dir: &Path, report: &Value) -> io::Result<()> {
+    fs::create_dir_all(dir)?;
+
+    let ts = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or_default();
+
+    let name = format!(
+        "{}-{}-{}",
+        ts,
+        std::process::id(),
+        SEQ.fetch_add(1, Ordering::Relaxed)
+    );
+
+    // Write to a temporary file first, so a partially written report is never picked up
+    let tmp_path = dir.join(format!("{name}.tmp"));
+    fs::write(&tmp_path, serde_json::to_vec(report)?)?;
+    fs::rename(tmp_path, dir.join(format!("{name}.json")))
+}
+
+/// Resends reports stored by previous runs from a background thread, deleting each one once delivered.
+pub fn resend(config: &Config, dir: PathBuf) {
+    let config = config.clone();
+
+    let res = thread::Builder::new()
+        .name("dontpanic-offline".into())
+        .spawn(move || {
+            if let Err(e) = resend_all(&config, &dir) {
+                eprintln!(
+                    "Error resending stored reports from {}. Error: {}",
+                    dir.display(),
+                    e
+                );
+            }
+        });
+
+    if let Err(e) = res {
+        eprintln!("Failed to spawn dontpanic offline thread. Error: {:?}", e);
+    }
+}
+
+fn resend_all(config: &Config, dir: &Path) -> io::Result<()> {
+    fs::create_dir_all(dir)?;
+
+    let mut paths: Vec<PathBuf> = fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+
+    // File names start with a timestamp, send the oldest reports first
+    paths.sort();
+
+    for path in paths {
+        if let Some(rate_limiter) = &config.rate_limiter {
+            if !rate_limiter.try_acquire() {
+                // Leave the remaining reports for the next startup
+                break;
+            }
+        }
+
+        let report = match fs::read(&path).map(|data| serde_json::from_slice::<Value>(&data)) {
+            Ok(Ok(report)) => report,
+            Ok(Err(e)) => {
+                eprintln!("Discarding invalid report {}. Error: {}", path.display(), e);
+                fs::remove_file(&path)?;
+                continue;
+            }
+            Err(e) => return Err(e),
+        };
+
+        match try_send_report(config, &report, config.max_retries) {
+            Ok(_) => (),
+            // The backend is still unreachable, try again on the next startup
+            Err(e) if e.is_retryable() => break,
+            Err(e) => eprintln!("Discarding rejected report {}. {}", path.display(), e),
+        }
+
+        fs::remove_file(&path)?;
+    }
+
+    Ok(())
+}
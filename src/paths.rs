@@ -0,0 +1,60 @@
+use super::ReportData;
+
+/// Makes file paths in a report relative to the first matching prefix, see [`Builder::strip_path_prefix`](crate::Builder::strip_path_prefix).
+///
+/// Applies to the location, log message and frame file paths, and to paths mentioned in the title and backtrace.
+pub fn strip(prefixes: &[String], mut data: ReportData) -> ReportData {
+    if prefixes.is_empty() {
+        return data;
+    }
+
+    if let Some(location) = &mut data.location {
+        strip_path(prefixes, &mut location.file);
+    }
+
+    for entry in &mut data.log {
+        if let Some(file) = &mut entry.file {
+            strip_path(prefixes, file);
+        }
+    }
+
+    for frame in &mut data.frames {
+        if let Some(file) = &mut frame.file {
+            strip_path(prefixes, file);
+        }
+    }
+
+    strip_text(prefixes, &mut data.title);
+
+    if let Some(backtrace) = &mut data.backtrace {
+        strip_text(prefixes, backtrace);
+    }
+
+    data
+}
+
+fn strip_path(prefixes: &[String], path: &mut String) {
+    for prefix in prefixes {
+        let Some(rest) = path.strip_prefix(prefix.as_str()) else {
+            continue;
+        };
+
+        // Only whole directory names match, `/home/ci` doesn't strip `/home/cid/src/main.rs`
+        if let Some(rest) = rest.strip_prefix(['/', '\\']) {
+            *path = rest.to_string();
+            return;
+        }
+    }
+}
+
+fn strip_text(prefixes: &[String], text: &mut String) {
+    for prefix in prefixes {
+        for separator in ['/', '\\'] {
+            let pattern = format!("{}{}", prefix, separator);
+
+            if text.contains(&pattern) {
+                *text = text.replace(&pattern, "");
+            }
+        }
+    }
+}
@@ -0,0 +1,57 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+/// Token bucket limiting how many reports are sent over a period of time.
+#[derive(Debug)]
+pub struct RateLimiter {
+    capacity: f64,
+    period: Duration,
+    bucket: Mutex<Bucket>,
+    dropped: AtomicU64,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+impl RateLimiter {
+    pub fn new(max_reports: u32, period: Duration) -> Self {
+        Self {
+            capacity: f64::from(max_reports),
+            period,
+            bucket: Mutex::new(Bucket {
+                tokens: f64::from(max_reports),
+                refilled_at: Instant::now(),
+            }),
+            dropped: AtomicU64::new(0),
+        }
+    }
+
+    /// Takes a token from the bucket. Returns `false` and counts the report as dropped if there are none left.
+    pub fn try_acquire(&self) -> bool {
+        let mut bucket = self.bucket.lock().unwrap_or_else(PoisonError::into_inner);
+
+        let now = Instant::now();
+        let elapsed = now.duration_since(bucket.refilled_at).as_secs_f64();
+        let refill = elapsed / self.period.as_secs_f64() * self.capacity;
+
+        bucket.tokens = (bucket.tokens + refill).min(self.capacity);
+        bucket.refilled_at = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            true
+        } else {
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+            false
+        }
+    }
+
+    /// Returns the number of reports dropped since the last call and resets the counter.
+    pub fn take_dropped(&self) -> u64 {
+        self.dropped.swap(0, Ordering::Relaxed)
+    }
+}
@@ -0,0 +1,352 @@
+use std::collections::HashMap;
+#[cfg(any(feature = "log", feature = "tracing"))]
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::Serialize;
+use serde_json::{self, Map, Value};
+
+/// Declares a struct of the wire format. A field followed by `=> "key"` is serialized with that [compact](FieldNaming::Compact) key,
+/// and with its own name in [verbose](FieldNaming::Verbose) mode, listed in the generated `KEYS`. Declaring both names in one place
+/// keeps the two schemas in sync.
+macro_rules! wire_struct {
+    (
+        $(#[$meta:meta])*
+        pub struct $name:ident $(<$lt:lifetime>)? {
+            $(
+                $(#[$field_meta:meta])*
+                pub $field:ident: $ty:ty $(=> $compact:literal)?,
+            )*
+        }
+    ) => {
+        $(#[$meta])*
+        pub struct $name $(<$lt>)? {
+            $(
+                $(#[$field_meta])*
+                $(#[serde(rename = $compact)])?
+                pub $field: $ty,
+            )*
+        }
+
+        impl $(<$lt>)? $name $(<$lt>)? {
+            /// Compact keys and their verbose counterparts
+            const KEYS: &'static [(&'static str, &'static str)] = &[$($(($compact, stringify!($field)),)?)*];
+        }
+    };
+}
+
+wire_struct! {
+    /// Location in the source code where a report originated.
+    #[derive(Clone, Debug, Serialize)]
+    pub struct ReportLocation {
+        /// Source file path
+        pub file: String => "f",
+        /// Line number
+        pub line: u32 => "l",
+        /// Column number, only available for panics
+        pub col: Option<u32> => "c",
+    }
+}
+
+impl ReportLocation {
+    /// Location of a panic or a `#[track_caller]` call site.
+    pub(crate) fn from_location(location: &std::panic::Location<'_>) -> Self {
+        Self {
+            file: location.file().to_string(),
+            line: location.line(),
+            // Columns are 1-based, zero means the column is unknown
+            col: Some(location.column()).filter(|&col| col > 0),
+        }
+    }
+}
+
+wire_struct! {
+    /// A log message or tracing event captured before a report was sent.
+    #[derive(Clone, Debug, Serialize)]
+    pub struct LogEntry {
+        /// Milliseconds since the UNIX epoch, 0 if [`clock_skew`](LogEntry::clock_skew) is set
+        pub timestamp: u64 => "ts",
+        /// The system clock was set before the UNIX epoch when the message was captured, so [`timestamp`](LogEntry::timestamp) is meaningless
+        #[serde(skip_serializing_if = "std::ops::Not::not")]
+        pub clock_skew: bool => "skew",
+        /// Severity, from 1 for error to 5 for trace
+        pub level: u8 => "lvl",
+        /// Formatted message
+        pub message: String => "msg",
+        /// Module path or tracing target
+        pub module: Option<String> => "mod",
+        /// Source file path
+        pub file: Option<String> => "f",
+        /// Line number
+        pub line: Option<u32> => "l",
+        /// Structured fields recorded with a tracing event, excluding the message, or the key-values of a log record with the `log-kv` feature enabled
+        pub fields: HashMap<String, String>,
+    }
+}
+
+wire_struct! {
+    /// A single frame of the stack a report was triggered from, see [`ReportData::frames`].
+    #[derive(Clone, Debug, Serialize)]
+    pub struct StackFrame {
+        /// Demangled function name, `None` without debug info
+        pub function: Option<String> => "fn",
+        /// Source file path
+        pub file: Option<String> => "f",
+        /// Line number
+        pub line: Option<u32> => "l",
+        /// Column number
+        pub col: Option<u32> => "c",
+    }
+}
+
+wire_struct! {
+    /// An event leading up to a report, added with [`Client::add_breadcrumb`](crate::Client::add_breadcrumb).
+    #[derive(Clone, Debug, Serialize)]
+    pub struct Breadcrumb {
+        /// Milliseconds since the UNIX epoch
+        pub timestamp: u64 => "ts",
+        /// Kind of event, e.g. `"navigation"` or `"user"`
+        pub category: String,
+        /// Description of the event
+        pub message: String => "msg",
+        /// Structured data describing the event
+        #[serde(skip_serializing_if = "HashMap::is_empty")]
+        pub data: HashMap<String, String>,
+    }
+}
+
+/// A tracing span that was active when a report was triggered.
+#[derive(Clone, Debug, Serialize)]
+pub struct SpanEntry {
+    /// Span name
+    pub name: String,
+    /// Span target, usually the module path
+    pub target: String,
+    /// Fields recorded on the span
+    pub fields: HashMap<String, String>,
+}
+
+wire_struct! {
+    /// All data sent to the backend server as part of a single report.
+    ///
+    /// Passed to [`Builder::before_send`](crate::Builder::before_send) callbacks before each report is sent.
+    /// Serializes to the `data` object of the wire format, the title and environment are part of the enclosing [`Report`].
+    #[derive(Clone, Debug, Serialize)]
+    #[non_exhaustive]
+    pub struct ReportData {
+        /// Report title. For panics this is the panic message followed by the location.
+        #[serde(skip)]
+        pub title: String,
+        /// Random UUID identifying the report, generated with the `uuid` feature enabled
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub event_id: Option<String>,
+        /// Environment set with [`Builder::environment`](crate::Builder::environment)
+        #[serde(skip)]
+        pub environment: Option<String>,
+        /// Version set with [`Builder::version`](crate::Builder::version)
+        pub version: Option<String> => "ver",
+        /// Release stage set with [`Builder::release`](crate::Builder::release)
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub release: Option<String>,
+        /// Git commit set with [`Builder::build_info`](crate::Builder::build_info)
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub commit: Option<String>,
+        /// Build time set with [`Builder::build_info`](crate::Builder::build_info)
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub built_at: Option<String>,
+        /// Machine hostname, see [`Builder::hostname`](crate::Builder::hostname)
+        pub hostname: Option<String> => "host",
+        /// Where the panic or error occurred
+        pub location: Option<ReportLocation> => "loc",
+        /// Id of the thread the report originated from
+        pub thread_id: String => "tid",
+        /// Name of the thread the report originated from
+        pub thread_name: Option<String> => "tname",
+        /// Process id
+        pub pid: u32,
+        /// Milliseconds since the client was built, which is usually close to the start of the process. Tells apart bugs hit
+        /// right at startup from slow leaks. `None` for fatal signals captured with the `signals` feature.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub uptime_ms: Option<u64>,
+        /// Path to the running executable
+        pub exe: Option<String>,
+        /// Current working directory, see [`Builder::capture_cwd`](crate::Builder::capture_cwd)
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub cwd: Option<String>,
+        /// Operating system, see [`std::env::consts::OS`]
+        pub os: String,
+        /// CPU architecture, see [`std::env::consts::ARCH`]
+        pub arch: String,
+        /// Version of the compiler the application was built with, e.g. `rustc 1.85.0 (4d91de4e4 2025-02-17)`
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub rustc: Option<String>,
+        /// Target triple the application was built for, e.g. `x86_64-unknown-linux-gnu`
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub target: Option<String>,
+        /// Cargo profile the application was built with, `"debug"` or `"release"`
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub profile: Option<String>,
+        /// Captured backtrace, `None` when disabled with [`Builder::backtrace`](crate::Builder::backtrace)
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub backtrace: Option<String> => "trace",
+        /// Structured frames of the backtrace, outermost call last. Only captured with the `frames` feature enabled, according to
+        /// [`Builder::backtrace`](crate::Builder::backtrace) and [`Builder::trim_backtrace`](crate::Builder::trim_backtrace).
+        #[serde(skip_serializing_if = "Vec::is_empty")]
+        pub frames: Vec<StackFrame>,
+        /// Resident memory of the process in bytes, see [`Builder::capture_memory`](crate::Builder::capture_memory)
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub rss_bytes: Option<u64>,
+        /// Number of open file descriptors, see [`Builder::capture_fd_count`](crate::Builder::capture_fd_count)
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub open_fds: Option<u64>,
+        /// Tags set with [`Builder::tag`](crate::Builder::tag)
+        pub tags: HashMap<String, String>,
+        /// Command line arguments, see [`Builder::capture_args`](crate::Builder::capture_args)
+        #[serde(skip_serializing_if = "Vec::is_empty")]
+        pub args: Vec<String>,
+        /// Environment variables listed with [`Builder::capture_env`](crate::Builder::capture_env)
+        #[serde(skip_serializing_if = "HashMap::is_empty")]
+        pub env_vars: HashMap<String, String>,
+        /// Log messages leading up to this report, oldest first
+        pub log: Vec<LogEntry>,
+        /// `"disconnected"` if log messages can no longer be captured, because the [`Client`](crate::Client) and every logger or
+        /// tracing layer created from it were dropped. An empty `log` then doesn't mean nothing was logged.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub log_capture: Option<String>,
+        /// Breadcrumbs added with [`Client::add_breadcrumb`](crate::Client::add_breadcrumb), oldest first
+        #[serde(skip_serializing_if = "Vec::is_empty")]
+        pub breadcrumbs: Vec<Breadcrumb>,
+        /// Last lines written to standard error, oldest first, see [`Builder::capture_stderr`](crate::Builder::capture_stderr)
+        #[serde(skip_serializing_if = "Vec::is_empty")]
+        pub stderr_tail: Vec<String>,
+        /// Number of reports dropped by the rate limiter since the last report was sent
+        pub dropped: u64,
+        /// Number of identical reports suppressed since this report was last sent, including panics at the same location
+        /// suppressed by [`Builder::per_location_cooldown`](crate::Builder::per_location_cooldown)
+        pub suppressed: u64,
+        /// Number of panics in the process so far, including this one, starting at 1. A high number hints at cascading failures.
+        /// Always `None` for reports that didn't originate from a panic.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub panic_seq: Option<u64>,
+        /// Rate the report was sampled at, see [`Builder::sample_rate`](crate::Builder::sample_rate). Always `None` for panics.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub sample_rate: Option<f64>,
+        /// Whether log messages, standard error lines, extra data or the backtrace were cut to fit [`Builder::max_payload_bytes`](crate::Builder::max_payload_bytes)
+        #[serde(skip_serializing_if = "std::ops::Not::not")]
+        pub truncated: bool,
+        /// Whether this is a report sent with [`Client::send_test_report`](crate::Client::send_test_report)
+        #[serde(skip_serializing_if = "std::ops::Not::not")]
+        pub test: bool,
+        /// Groups reports of the same bug on the server, see [`Builder::fingerprint_fn`](crate::Builder::fingerprint_fn)
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub fingerprint: Option<String>,
+        /// Tracing spans the report was triggered in, outermost first
+        pub spans: Vec<SpanEntry>,
+        /// Additional context passed to [`Client::report_error`](crate::Client::report_error) and set with [`with_context`](crate::with_context)
+        /// or [`Client::set_context`](crate::Client::set_context)
+        pub context: HashMap<String, String>,
+        /// Error chain of an error passed to [`Client::report`](crate::Client::report), excluding the error itself
+        pub causes: Vec<String>,
+        /// Arbitrary data passed to [`Client::report_with_extra`](crate::Client::report_with_extra)
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub extra: Option<Value>,
+    }
+}
+
+/// Key names used in the wire format, see [`Builder::field_naming`](crate::Builder::field_naming).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FieldNaming {
+    /// Short keys like `msg`, `lvl` and `ts`, as documented on [`Report`], [`ReportData`] and the types they contain
+    #[default]
+    Compact,
+    /// Readable keys like `message`, `level` and `timestamp`, matching the field names of [`Report`], [`ReportData`] and the types they contain
+    Verbose,
+}
+
+wire_struct! {
+    /// A report as sent to the backend server.
+    ///
+    /// This is the complete wire format, serializing it with `serde_json` produces the request body with [compact](FieldNaming::Compact) keys.
+    /// With [`Builder::batch`](crate::Builder::batch) enabled, the request body is a JSON array of reports instead.
+    #[derive(Clone, Copy, Debug, Serialize)]
+    #[non_exhaustive]
+    pub struct Report<'a> {
+        /// Project API key
+        pub api_key: &'a str => "key",
+        /// Environment set with [`Builder::environment`](crate::Builder::environment)
+        pub environment: Option<&'a str> => "env",
+        /// Report title
+        pub title: &'a str => "name",
+        /// Everything else included in the report
+        pub data: &'a ReportData,
+    }
+}
+
+impl<'a> Report<'a> {
+    /// Creates the report sent for `data` to the project identified by `api_key`.
+    pub fn new(api_key: &'a str, data: &'a ReportData) -> Self {
+        Self {
+            api_key,
+            environment: data.environment.as_deref(),
+            title: &data.title,
+            data,
+        }
+    }
+
+    pub(crate) fn to_json(self, naming: FieldNaming) -> Value {
+        // Only strings, numbers and string keyed maps are serialized, which can't fail
+        let mut report = serde_json::to_value(self).unwrap_or_default();
+
+        if naming == FieldNaming::Verbose {
+            verbose_keys(&mut report);
+        }
+
+        report
+    }
+}
+
+/// Renames the compact keys of a serialized [`Report`]. Only known objects are touched, keys of user provided maps like
+/// tags or context are kept as they are.
+fn verbose_keys(report: &mut Value) {
+    let Some(report) = report.as_object_mut() else {
+        return;
+    };
+
+    if let Some(data) = report.get_mut("data").and_then(Value::as_object_mut) {
+        if let Some(location) = data.get_mut("loc").and_then(Value::as_object_mut) {
+            rename_keys(location, ReportLocation::KEYS);
+        }
+
+        for (key, names) in [
+            ("log", LogEntry::KEYS),
+            ("frames", StackFrame::KEYS),
+            ("breadcrumbs", Breadcrumb::KEYS),
+        ] {
+            if let Some(entries) = data.get_mut(key).and_then(Value::as_array_mut) {
+                for entry in entries.iter_mut().filter_map(Value::as_object_mut) {
+                    rename_keys(entry, names);
+                }
+            }
+        }
+
+        rename_keys(data, ReportData::KEYS);
+    }
+
+    rename_keys(report, Report::KEYS);
+}
+
+fn rename_keys(object: &mut Map<String, Value>, names: &[(&str, &str)]) {
+    for (compact, verbose) in names {
+        if let Some(value) = object.remove(*compact) {
+            object.insert(verbose.to_string(), value);
+        }
+    }
+}
+
+/// Current time in milliseconds since the UNIX epoch, `None` if the system clock is set before it.
+#[cfg(any(feature = "log", feature = "tracing"))]
+pub(crate) fn now_millis() -> Option<u64> {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .ok()
+        .map(|d| d.as_millis() as u64)
+}
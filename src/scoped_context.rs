@@ -0,0 +1,69 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+thread_local! {
+    /// Context set with [`with_context`] on the current thread.
+    static SCOPED: RefCell<HashMap<String, String>> = RefCell::new(HashMap::new());
+}
+
+/// Attach a key-value pair to reports created on the current thread while `f` runs, e.g. a request or tenant id in a server
+/// handling requests concurrently. Unlike [`Client::set_context`](crate::Client::set_context), the context doesn't leak into reports
+/// from other threads. Returns the result of `f`.
+///
+/// Calls can be nested, and an inner call with the same key overrides the outer value until it returns. Context passed to
+/// [`Client::report_error`](crate::Client::report_error) takes precedence over keys set here, which take precedence over
+/// [`Client::set_context`](crate::Client::set_context).
+///
+/// The context is bound to the thread, in async code only reports created while `f` runs synchronously include it.
+///
+/// ```no_run
+/// use anyhow::Result;
+///
+/// fn main() -> Result<()> {
+///     let dontpanic = dontpanic::builder("<PROJECT_API_KEY>").build()?;
+///
+///     dontpanic::with_context("request_id", "8f14e45f", || {
+///         dontpanic.report_error("Upstream service unavailable", None);
+///     });
+///
+///     Ok(())
+/// }
+/// ```
+pub fn with_context<R>(
+    key: impl Into<String>,
+    value: impl Into<String>,
+    f: impl FnOnce() -> R,
+) -> R {
+    let key = key.into();
+    let previous = SCOPED.with_borrow_mut(|context| context.insert(key.clone(), value.into()));
+
+    // Restored on unwind as well, after the panic hook reported the panic with the context still set
+    let _restore = Restore { key, previous };
+
+    f()
+}
+
+/// Context set on the current thread with [`with_context`].
+pub(crate) fn current() -> HashMap<String, String> {
+    SCOPED
+        .try_with(|context| context.borrow().clone())
+        .unwrap_or_default()
+}
+
+struct Restore {
+    key: String,
+    previous: Option<String>,
+}
+
+impl Drop for Restore {
+    fn drop(&mut self) {
+        let _ = SCOPED.try_with(|context| {
+            let mut context = context.borrow_mut();
+
+            match self.previous.take() {
+                Some(previous) => context.insert(self.key.clone(), previous),
+                None => context.remove(&self.key),
+            };
+        });
+    }
+}
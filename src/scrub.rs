@@ -0,0 +1,119 @@
+use std::borrow::Cow;
+
+use regex::Regex;
+use serde_json::Value;
+
+use super::ReportData;
+
+/// Replaces every match of a scrub pattern.
+const REDACTED: &str = "[redacted]";
+
+/// Patterns added by [`Builder::scrub_defaults`](crate::Builder::scrub_defaults).
+const DEFAULT_PATTERNS: &[&str] = &[
+    // Email addresses
+    r"[A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\.[A-Za-z]{2,}",
+    // 13 to 19 digits, optionally grouped with spaces or dashes, like credit card numbers
+    r"\b\d(?:[ -]?\d){12,18}\b",
+    // Bearer tokens, e.g. from logged Authorization headers
+    r"(?i)\bbearer\s+[A-Za-z0-9\-._~+/]+=*",
+];
+
+/// Compiles the built-in patterns.
+pub fn default_patterns() -> Vec<Regex> {
+    DEFAULT_PATTERNS
+        .iter()
+        .map(|pattern| Regex::new(pattern).expect("default scrub patterns are valid"))
+        .collect()
+}
+
+/// Redacts matches of `patterns` in every free-text field of a report.
+pub fn apply(patterns: &[Regex], mut data: ReportData) -> ReportData {
+    if patterns.is_empty() {
+        return data;
+    }
+
+    scrub(patterns, &mut data.title);
+
+    // The default fingerprint is derived from the title
+    if let Some(fingerprint) = &mut data.fingerprint {
+        scrub(patterns, fingerprint);
+    }
+
+    for entry in &mut data.log {
+        scrub(patterns, &mut entry.message);
+
+        for value in entry.fields.values_mut() {
+            scrub(patterns, value);
+        }
+    }
+
+    for line in &mut data.stderr_tail {
+        scrub(patterns, line);
+    }
+
+    for breadcrumb in &mut data.breadcrumbs {
+        scrub(patterns, &mut breadcrumb.message);
+
+        for value in breadcrumb.data.values_mut() {
+            scrub(patterns, value);
+        }
+    }
+
+    for cause in &mut data.causes {
+        scrub(patterns, cause);
+    }
+
+    for span in &mut data.spans {
+        for value in span.fields.values_mut() {
+            scrub(patterns, value);
+        }
+    }
+
+    for value in data.context.values_mut() {
+        scrub(patterns, value);
+    }
+
+    if let Some(extra) = &mut data.extra {
+        scrub_value(patterns, extra);
+    }
+
+    for arg in &mut data.args {
+        scrub(patterns, arg);
+    }
+
+    for value in data.env_vars.values_mut() {
+        scrub(patterns, value);
+    }
+
+    if let Some(backtrace) = &mut data.backtrace {
+        scrub(patterns, backtrace);
+    }
+
+    data
+}
+
+fn scrub(patterns: &[Regex], text: &mut String) {
+    for pattern in patterns {
+        if let Cow::Owned(scrubbed) = pattern.replace_all(text, REDACTED) {
+            *text = scrubbed;
+        }
+    }
+}
+
+/// Redacts every string in a JSON tree, object keys excluded.
+fn scrub_value(patterns: &[Regex], value: &mut Value) {
+    match value {
+        Value::String(text) => scrub(patterns, text),
+        Value::Array(values) => {
+            for value in values {
+                scrub_value(patterns, value);
+            }
+        }
+        Value::Object(object) => {
+            for value in object.values_mut() {
+                scrub_value(patterns, value);
+            }
+        }
+        Value::Null | Value::Bool(_) | Value::Number(_) => (),
+    }
+}
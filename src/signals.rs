@@ -0,0 +1,211 @@
+use std::ffi::CString;
+use std::fs;
+use std::io;
+use std::os::unix::ffi::OsStrExt;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+use std::time::{SystemTime, UNIX_EPOCH};
+use std::{mem, ptr};
+
+use libc::c_int;
+use serde_json::Value;
+
+/// Stands in for the signal name in the pre-serialized report, replaced when the report is written.
+const MARKER: &str = "__DONTPANIC_SIGNAL__";
+
+/// Signals caught by the handler, with the names reported for them.
+const SIGNALS: &[(c_int, &str)] = &[
+    (libc::SIGSEGV, "SIGSEGV"),
+    (libc::SIGBUS, "SIGBUS"),
+    (libc::SIGILL, "SIGILL"),
+    (libc::SIGFPE, "SIGFPE"),
+    (libc::SIGABRT, "SIGABRT"),
+];
+
+/// Signal handlers are process wide, so they're installed once, for the first client that asks for it.
+static HANDLER: OnceLock<Handler> = OnceLock::new();
+
+/// Set by the first caught signal, so threads crashing at the same time don't write the report file concurrently.
+static HANDLING: AtomicBool = AtomicBool::new(false);
+
+/// Set by the panic hook once it reported a panic. With `panic = "abort"` or on a double panic, the process aborts right after
+/// the hook, and the resulting SIGABRT would otherwise be reported a second time.
+static PANIC_REPORTED: AtomicBool = AtomicBool::new(false);
+
+/// Everything the signal handler needs, prepared in advance, since it can't allocate or take locks.
+struct Handler {
+    /// The pre-serialized report, split at every occurrence of [`MARKER`]
+    parts: Vec<Vec<u8>>,
+    tmp_path: CString,
+    path: CString,
+    /// Dispositions that were in place before, restored before the signal is raised again
+    previous: Vec<(c_int, libc::sigaction)>,
+}
+
+/// Title of the pre-serialized report, see [`install`].
+pub fn title() -> String {
+    format!("Fatal signal {}", MARKER)
+}
+
+/// Called by the panic hook after reporting a panic, so a following SIGABRT isn't reported again.
+pub fn panic_reported() {
+    PANIC_REPORTED.store(true, Ordering::SeqCst);
+}
+
+/// Installs handlers for fatal signals that write `report` to the offline directory `dir`, with the signal name filled in
+/// wherever [`title`] put a placeholder. The report is resent the next time a client is built with the same directory.
+pub fn install(dir: &Path, report: &Value) -> io::Result<()> {
+    if HANDLER.get().is_some() {
+        return Ok(());
+    }
+
+    fs::create_dir_all(dir)?;
+
+    let ts = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or_default();
+
+    // Same naming scheme as reports stored by `offline::store`, so it's picked up and sorted the same way
+    let name = format!("{}-{}-signal", ts, std::process::id());
+    let tmp_path = CString::new(dir.join(format!("{name}.tmp")).as_os_str().as_bytes())?;
+    let path = CString::new(dir.join(format!("{name}.json")).as_os_str().as_bytes())?;
+
+    let parts = serde_json::to_string(report)?
+        .split(MARKER)
+        .map(|part| part.as_bytes().to_vec())
+        .collect();
+
+    let mut previous = Vec::with_capacity(SIGNALS.len());
+
+    for &(signal, _) in SIGNALS {
+        // SAFETY: a zeroed sigaction is valid, and passing a null new action only reads the current one
+        let action = unsafe {
+            let mut action: libc::sigaction = mem::zeroed();
+
+            if libc::sigaction(signal, ptr::null(), &mut action) == -1 {
+                return Err(io::Error::last_os_error());
+            }
+
+            action
+        };
+
+        previous.push((signal, action));
+    }
+
+    // Another client may have raced this one
+    if HANDLER
+        .set(Handler {
+            parts,
+            tmp_path,
+            path,
+            previous,
+        })
+        .is_err()
+    {
+        return Ok(());
+    }
+
+    for &(signal, _) in SIGNALS {
+        // SAFETY: `handle` only calls async-signal-safe functions and reads `HANDLER`, which is fully initialized at this point.
+        // SA_ONSTACK runs it on the alternate signal stack set up by the standard library, so stack overflows can be handled too.
+        unsafe {
+            let mut action: libc::sigaction = mem::zeroed();
+            action.sa_sigaction = handle as *const () as usize;
+            action.sa_flags = libc::SA_SIGINFO | libc::SA_ONSTACK;
+            libc::sigemptyset(&mut action.sa_mask);
+
+            if libc::sigaction(signal, &action, ptr::null_mut()) == -1 {
+                return Err(io::Error::last_os_error());
+            }
+        }
+    }
+
+    Ok(())
+}
+
+extern "C" fn handle(signal: c_int, info: *mut libc::siginfo_t, _context: *mut libc::c_void) {
+    let Some(handler) = HANDLER.get() else {
+        return;
+    };
+
+    let panicked = signal == libc::SIGABRT && PANIC_REPORTED.load(Ordering::SeqCst);
+
+    if !panicked && !HANDLING.swap(true, Ordering::SeqCst) {
+        let name = SIGNALS
+            .iter()
+            .find(|(caught, _)| *caught == signal)
+            .map_or("UNKNOWN", |(_, name)| name);
+
+        handler.write(name);
+    }
+
+    if let Some((_, previous)) = handler
+        .previous
+        .iter()
+        .find(|(caught, _)| *caught == signal)
+    {
+        // SAFETY: restores a disposition read from sigaction before
+        unsafe { libc::sigaction(signal, previous, ptr::null_mut()) };
+    }
+
+    // Faults raised by the kernel happen again once the handler returns, now handled by the previous disposition. This keeps
+    // e.g. the standard library's stack overflow message intact. Signals sent with kill or raise, including SIGABRT from
+    // abort, have to be raised again.
+    // SAFETY: the kernel passes a valid siginfo to SA_SIGINFO handlers
+    if unsafe { (*info).si_code } <= 0 {
+        // SAFETY: raise is async-signal-safe
+        unsafe { libc::raise(signal) };
+    }
+}
+
+impl Handler {
+    /// Writes the report to a temporary file and renames it once complete, so a partially written report is never resent.
+    /// Only calls async-signal-safe functions and doesn't allocate.
+    fn write(&self, name: &str) {
+        // SAFETY: the paths are valid C strings, and the file descriptor is only used while open
+        unsafe {
+            let fd = libc::open(
+                self.tmp_path.as_ptr(),
+                libc::O_WRONLY | libc::O_CREAT | libc::O_TRUNC | libc::O_CLOEXEC,
+                0o600 as libc::c_uint,
+            );
+
+            if fd == -1 {
+                return;
+            }
+
+            let mut written = true;
+
+            for (i, part) in self.parts.iter().enumerate() {
+                if i > 0 {
+                    written &= write_all(fd, name.as_bytes());
+                }
+
+                written &= write_all(fd, part);
+            }
+
+            libc::close(fd);
+
+            if written {
+                libc::rename(self.tmp_path.as_ptr(), self.path.as_ptr());
+            }
+        }
+    }
+}
+
+fn write_all(fd: c_int, mut buf: &[u8]) -> bool {
+    while !buf.is_empty() {
+        // SAFETY: the pointer and length describe a valid slice
+        let written = unsafe { libc::write(fd, buf.as_ptr().cast(), buf.len()) };
+
+        if written <= 0 {
+            return false;
+        }
+
+        buf = &buf[written as usize..];
+    }
+
+    true
+}
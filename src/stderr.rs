@@ -0,0 +1,123 @@
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{self, Read, Write};
+use std::os::fd::{AsRawFd, FromRawFd};
+use std::sync::{Arc, Mutex, OnceLock, PoisonError};
+use std::thread;
+
+/// The tee is process wide, so it's installed once and shared by every client. `None` if installing it failed.
+static TAIL: OnceLock<Option<Arc<StderrTail>>> = OnceLock::new();
+
+/// The last lines written to standard error, see [`Builder::capture_stderr`](crate::Builder::capture_stderr).
+#[derive(Debug)]
+pub struct StderrTail {
+    lines: Mutex<VecDeque<String>>,
+    capacity: usize,
+}
+
+impl StderrTail {
+    pub fn lines(&self) -> Vec<String> {
+        self.lines
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .iter()
+            .cloned()
+            .collect()
+    }
+
+    fn push(&self, line: &[u8]) {
+        let mut lines = self.lines.lock().unwrap_or_else(PoisonError::into_inner);
+
+        if lines.len() == self.capacity {
+            lines.pop_front();
+        }
+
+        lines.push_back(String::from_utf8_lossy(line).into_owned());
+    }
+}
+
+/// Redirects standard error into a pipe, read by a background thread that forwards everything to the original
+/// standard error and keeps the last `capacity` lines. Only the first call installs the tee, concurrent calls wait for it.
+pub fn install(capacity: usize) -> io::Result<Arc<StderrTail>> {
+    let mut error = None;
+
+    let tail = TAIL.get_or_init(|| match redirect(capacity) {
+        Ok(tail) => Some(tail),
+        Err(e) => {
+            error = Some(e);
+            None
+        }
+    });
+
+    match (tail, error) {
+        (Some(tail), _) => Ok(tail.clone()),
+        (None, Some(e)) => Err(e),
+        (None, None) => Err(io::Error::other(
+            "capturing standard error failed previously",
+        )),
+    }
+}
+
+fn redirect(capacity: usize) -> io::Result<Arc<StderrTail>> {
+    let (mut reader, writer) = io::pipe()?;
+
+    // SAFETY: dup returns a new file descriptor that nothing else owns, or -1 which is checked
+    let original = unsafe {
+        let fd = libc::dup(libc::STDERR_FILENO);
+
+        if fd == -1 {
+            return Err(io::Error::last_os_error());
+        }
+
+        File::from_raw_fd(fd)
+    };
+
+    let tail = Arc::new(StderrTail {
+        lines: Mutex::new(VecDeque::with_capacity(capacity)),
+        capacity,
+    });
+
+    let thread_tail = tail.clone();
+
+    // Start reading before redirecting, so standard error never refers to a pipe nobody reads from
+    thread::Builder::new()
+        .name("dontpanic-stderr".into())
+        .spawn(move || tee(&mut reader, original, &thread_tail))?;
+
+    // SAFETY: both file descriptors are valid for the duration of the call
+    if unsafe { libc::dup2(writer.as_raw_fd(), libc::STDERR_FILENO) } == -1 {
+        // Standard error is left untouched, and dropping the writer ends the thread
+        return Err(io::Error::last_os_error());
+    }
+
+    // Standard error now refers to the pipe on its own
+    drop(writer);
+
+    Ok(tail)
+}
+
+fn tee(reader: &mut impl Read, mut original: File, tail: &StderrTail) {
+    let mut buf = [0; 4096];
+    let mut line = Vec::new();
+
+    loop {
+        let read = match reader.read(&mut buf) {
+            Ok(0) => break,
+            Ok(read) => read,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(_) => break,
+        };
+
+        // Forward right away, output without a trailing newline like prompts shouldn't wait for the line to complete
+        let _ = original.write_all(&buf[..read]);
+
+        for &byte in &buf[..read] {
+            if byte == b'\n' {
+                tail.push(&line);
+                line.clear();
+            } else {
+                line.push(byte);
+            }
+        }
+    }
+}
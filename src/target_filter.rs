@@ -0,0 +1,25 @@
+/// Selects which log and tracing targets trigger a report on errors, see [`Builder::report_error_targets`](crate::Builder::report_error_targets).
+///
+/// Targets are matched by prefix, so `"my_app"` matches `my_app::db` as well. For log records the target defaults to the module path.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TargetFilter {
+    /// Only errors from targets starting with one of these prefixes trigger a report.
+    Allowlist(Vec<String>),
+    /// Errors from targets starting with one of these prefixes don't trigger a report.
+    Denylist(Vec<String>),
+}
+
+impl TargetFilter {
+    pub(crate) fn allows(&self, target: &str) -> bool {
+        let matches = |prefixes: &[String]| {
+            prefixes
+                .iter()
+                .any(|prefix| target.starts_with(prefix.as_str()))
+        };
+
+        match self {
+            Self::Allowlist(prefixes) => matches(prefixes),
+            Self::Denylist(prefixes) => !matches(prefixes),
+        }
+    }
+}
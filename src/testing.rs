@@ -0,0 +1,316 @@
+//! Helpers for testing applications that report with dontpanic, without a running backend server.
+
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Condvar, Mutex, PoisonError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use flate2::read::GzDecoder;
+use serde_json::{self, Value};
+
+use super::ReportData;
+
+/// A local stand-in for the backend server, recording every report it receives.
+///
+/// Binds a random port on `127.0.0.1` and accepts reports posted to `/ingress`, so pointing [`Builder::backend_url`](crate::Builder::backend_url)
+/// at [`url`](MockServer::url) is enough. Gzip compressed, [MessagePack](crate::WireFormat) encoded and [batched](crate::Builder::batch) reports
+/// are supported, and [pings](crate::Client::ping) always succeed. The server stops when dropped.
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use anyhow::Result;
+/// use dontpanic::testing::MockServer;
+///
+/// fn main() -> Result<()> {
+///     let server = MockServer::start()?;
+///
+///     dontpanic::builder("<PROJECT_API_KEY>")
+///         .backend_url(server.url())
+///         .build()?;
+///
+///     let _ = std::panic::catch_unwind(|| panic!("Boom"));
+///
+///     let reports = server.wait_for_reports(1, Duration::from_secs(5));
+///     assert_eq!(reports.len(), 1);
+///     assert_eq!(reports[0]["key"], "<PROJECT_API_KEY>");
+///     assert!(reports[0]["name"].as_str().unwrap().starts_with("Boom"));
+///
+///     Ok(())
+/// }
+/// ```
+pub struct MockServer {
+    url: String,
+    received: Arc<Received>,
+    shutdown: Arc<AtomicBool>,
+}
+
+#[derive(Default)]
+struct Received {
+    reports: Mutex<Vec<Value>>,
+    changed: Condvar,
+}
+
+impl MockServer {
+    /// Starts the server on a background thread.
+    pub fn start() -> io::Result<Self> {
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let url = format!("http://{}", listener.local_addr()?);
+
+        let received = Arc::new(Received::default());
+        let shutdown = Arc::new(AtomicBool::new(false));
+
+        {
+            let received = received.clone();
+            let shutdown = shutdown.clone();
+
+            thread::Builder::new()
+                .name("dontpanic-mock-server".into())
+                .spawn(move || {
+                    for stream in listener.incoming() {
+                        if shutdown.load(Ordering::Relaxed) {
+                            break;
+                        }
+
+                        if let Ok(stream) = stream {
+                            if let Err(e) = handle(stream, &received) {
+                                eprintln!("Mock server failed to handle request. Error: {}", e);
+                            }
+                        }
+                    }
+                })?;
+        }
+
+        Ok(Self {
+            url,
+            received,
+            shutdown,
+        })
+    }
+
+    /// Base url of the server, to be passed to [`Builder::backend_url`](crate::Builder::backend_url).
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// All reports received so far, oldest first. Batches are split into the individual reports they contain.
+    pub fn reports(&self) -> Vec<Value> {
+        self.received
+            .reports
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// Blocks until at least `count` reports were received or `timeout` elapsed, then returns all received reports.
+    pub fn wait_for_reports(&self, count: usize, timeout: Duration) -> Vec<Value> {
+        let deadline = Instant::now() + timeout;
+        let mut reports = self
+            .received
+            .reports
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+
+        while reports.len() < count {
+            let Some(remaining) = deadline.checked_duration_since(Instant::now()) else {
+                break;
+            };
+
+            reports = self
+                .received
+                .changed
+                .wait_timeout(reports, remaining)
+                .unwrap_or_else(PoisonError::into_inner)
+                .0;
+        }
+
+        reports.clone()
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        self.shutdown.store(true, Ordering::Relaxed);
+
+        // Wake up the accept loop, so it notices the shutdown flag
+        let _ = TcpStream::connect(self.url.trim_start_matches("http://"));
+    }
+}
+
+fn handle(stream: TcpStream, received: &Received) -> io::Result<()> {
+    let mut reader = BufReader::new(stream.try_clone()?);
+
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+
+    let mut content_length = 0;
+    let mut gzip = false;
+    let mut msgpack = false;
+
+    loop {
+        let mut line = String::new();
+
+        if reader.read_line(&mut line)? == 0 || line.trim().is_empty() {
+            break;
+        }
+
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+
+        if name.eq_ignore_ascii_case("content-length") {
+            content_length = value.trim().parse().unwrap_or_default();
+        } else if name.eq_ignore_ascii_case("content-encoding") {
+            gzip = value.trim().eq_ignore_ascii_case("gzip");
+        } else if name.eq_ignore_ascii_case("content-type") {
+            msgpack = value.trim().eq_ignore_ascii_case("application/msgpack");
+        }
+    }
+
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body)?;
+
+    let mut parts = request_line.split_whitespace();
+    let is_post = parts.next() == Some("POST");
+    let path = parts.next().unwrap_or_default().trim_end_matches('/');
+    let is_ingress = is_post && path.ends_with("/ingress");
+    let is_ping = is_post && path.ends_with("/ingress/ping");
+
+    let status = if is_ping || (is_ingress && record(&body, gzip, msgpack, received).is_ok()) {
+        "200 OK"
+    } else if is_ingress {
+        "400 Bad Request"
+    } else {
+        "404 Not Found"
+    };
+
+    write!(
+        &stream,
+        "HTTP/1.1 {}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+        status
+    )?;
+
+    (&stream).flush()
+}
+
+fn record(body: &[u8], gzip: bool, msgpack: bool, received: &Received) -> io::Result<()> {
+    let mut decoded = Vec::new();
+
+    let body = if gzip {
+        GzDecoder::new(body).read_to_end(&mut decoded)?;
+        &decoded
+    } else {
+        body
+    };
+
+    let report: Value = if msgpack {
+        decode_msgpack(body)?
+    } else {
+        serde_json::from_slice(body)?
+    };
+
+    let mut reports = received
+        .reports
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner);
+
+    match report {
+        Value::Array(batch) => reports.extend(batch),
+        report => reports.push(report),
+    }
+
+    received.changed.notify_all();
+
+    Ok(())
+}
+
+#[cfg(feature = "msgpack")]
+fn decode_msgpack(body: &[u8]) -> io::Result<Value> {
+    rmp_serde::from_slice(body).map_err(io::Error::other)
+}
+
+#[cfg(not(feature = "msgpack"))]
+fn decode_msgpack(_body: &[u8]) -> io::Result<Value> {
+    Err(io::Error::new(
+        io::ErrorKind::InvalidData,
+        "MessagePack reports require the msgpack feature",
+    ))
+}
+
+/// Records every report a [`Client`](crate::Client) sends, in memory, for asserting on reports without any HTTP server.
+/// Returned by [`Client::test_sink`](crate::Client::test_sink).
+///
+/// Reports are recorded exactly as they are sent, after [`before_send`](crate::Builder::before_send), scrubbing and truncation,
+/// and regardless of whether sending succeeds. Combine with [`Builder::build_disabled`](crate::Builder::build_disabled) to not send them at all.
+///
+/// ```
+/// use anyhow::Result;
+///
+/// fn main() -> Result<()> {
+///     let dontpanic = dontpanic::builder("<PROJECT_API_KEY>").build_disabled()?;
+///     let sink = dontpanic.test_sink();
+///
+///     dontpanic.report_error("Config file missing", None);
+///     dontpanic.flush();
+///
+///     let reports = sink.reports();
+///     assert_eq!(reports.len(), 1);
+///     assert_eq!(reports[0].title, "Config file missing");
+///
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Default)]
+pub struct TestSink {
+    reports: Mutex<Vec<ReportData>>,
+    changed: Condvar,
+}
+
+impl TestSink {
+    /// All reports recorded so far, oldest first.
+    pub fn reports(&self) -> Vec<ReportData> {
+        self.reports
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// Blocks until at least `count` reports were recorded or `timeout` elapsed, then returns all recorded reports.
+    pub fn wait_for_reports(&self, count: usize, timeout: Duration) -> Vec<ReportData> {
+        let deadline = Instant::now() + timeout;
+        let mut reports = self.reports.lock().unwrap_or_else(PoisonError::into_inner);
+
+        while reports.len() < count {
+            let Some(remaining) = deadline.checked_duration_since(Instant::now()) else {
+                break;
+            };
+
+            reports = self
+                .changed
+                .wait_timeout(reports, remaining)
+                .unwrap_or_else(PoisonError::into_inner)
+                .0;
+        }
+
+        reports.clone()
+    }
+
+    /// Removes all recorded reports.
+    pub fn clear(&self) {
+        self.reports
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clear();
+    }
+
+    pub(crate) fn record(&self, data: &[ReportData]) {
+        self.reports
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .extend_from_slice(data);
+
+        self.changed.notify_all();
+    }
+}
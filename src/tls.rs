@@ -0,0 +1,37 @@
+use std::sync::Arc;
+
+use rustls::pki_types::pem::PemObject;
+use rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use rustls::{ClientConfig, RootCertStore};
+
+use super::Error;
+
+/// Builds the TLS configuration of the built-in transport, authenticating with a client certificate.
+///
+/// Uses the same crypto provider and root certificates as ureq's default configuration.
+pub fn client_auth_config(cert_pem: &[u8], key_pem: &[u8]) -> Result<Arc<ClientConfig>, Error> {
+    let certs = CertificateDer::pem_slice_iter(cert_pem)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| Error::Tls(format!("invalid certificate: {}", e)))?;
+
+    if certs.is_empty() {
+        return Err(Error::Tls("no certificate found".into()));
+    }
+
+    let key = PrivateKeyDer::from_pem_slice(key_pem)
+        .map_err(|e| Error::Tls(format!("invalid private key: {}", e)))?;
+
+    let roots = RootCertStore {
+        roots: webpki_roots::TLS_SERVER_ROOTS.to_vec(),
+    };
+
+    let config =
+        ClientConfig::builder_with_provider(rustls::crypto::ring::default_provider().into())
+            .with_safe_default_protocol_versions()
+            .map_err(|e| Error::Tls(e.to_string()))?
+            .with_root_certificates(roots)
+            .with_client_auth_cert(certs, key)
+            .map_err(|e| Error::Tls(e.to_string()))?;
+
+    Ok(Arc::new(config))
+}
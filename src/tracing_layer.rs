@@ -1,18 +1,37 @@
+use std::collections::HashMap;
 use std::fmt::{self, Write};
-use std::sync::atomic::Ordering;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::sync::Arc;
 
-use ring_channel::{RingReceiver, RingSender};
 use tracing::{
     field::{Field, Visit},
-    Event, Level, Subscriber,
+    span, Event, Subscriber,
 };
-use tracing_subscriber::{layer::Context, Layer};
+use tracing_subscriber::{layer::Context, registry::LookupSpan, Layer};
 
-use super::{send_report, Config, LogEvent, ReportLocation};
+use super::level::Severity;
+use super::log_buffer::{LogBuffer, LogSender};
+use super::log_snapshot;
+use super::report::now_millis;
+use super::{
+    create_sampled_report, worker, Config, FieldFormatter, LogEntry, Logs, ReportLocation,
+    SpanEntry, SENDING,
+};
 
 pub struct MessageVisitor<'a> {
     message: &'a mut String,
+    formatter: Option<&'a FieldFormatter>,
+}
+
+impl MessageVisitor<'_> {
+    fn push_field(
+        &mut self,
+        field: &Field,
+        value: &dyn fmt::Debug,
+        default: impl FnOnce() -> String,
+    ) {
+        let value = format_value(self.formatter, field, value, default);
+        write!(self.message, "{}={} ", field.name(), value).unwrap();
+    }
 }
 
 impl<'a> Visit for MessageVisitor<'a> {
@@ -20,7 +39,7 @@ impl<'a> Visit for MessageVisitor<'a> {
         if field.name() == "message" {
             write!(self.message, "{:?}", value).unwrap();
         } else {
-            write!(self.message, "{}={:?} ", field.name(), value).unwrap();
+            self.push_field(field, value, || format!("{:?}", value));
         }
     }
 
@@ -28,23 +47,155 @@ impl<'a> Visit for MessageVisitor<'a> {
         if field.name() == "message" {
             write!(self.message, "{}", value).unwrap();
         } else {
-            write!(self.message, "{}={:?} ", field.name(), value).unwrap();
+            self.push_field(field, &value, || value.to_string());
+        }
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.push_field(field, &value, || value.to_string());
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.push_field(field, &value, || value.to_string());
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.push_field(field, &value, || value.to_string());
+    }
+
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.push_field(field, &value, || value.to_string());
+    }
+}
+
+/// Collects fields into a map of field names to their formatted values.
+struct FieldVisitor<'a> {
+    fields: &'a mut HashMap<String, String>,
+    formatter: Option<&'a FieldFormatter>,
+}
+
+impl FieldVisitor<'_> {
+    fn insert(&mut self, field: &Field, value: &dyn fmt::Debug, default: impl FnOnce() -> String) {
+        let value = format_value(self.formatter, field, value, default);
+        self.fields.insert(field.name().to_string(), value);
+    }
+}
+
+impl<'a> Visit for FieldVisitor<'a> {
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.insert(field, value, || format!("{:?}", value));
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.insert(field, &value, || value.to_string());
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.insert(field, &value, || value.to_string());
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.insert(field, &value, || value.to_string());
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.insert(field, &value, || value.to_string());
+    }
+
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.insert(field, &value, || value.to_string());
+    }
+}
+
+/// Renders a field value with the [`Builder::field_formatter`](crate::Builder::field_formatter) if one is set, `default` otherwise.
+fn format_value(
+    formatter: Option<&FieldFormatter>,
+    field: &Field,
+    value: &dyn fmt::Debug,
+    default: impl FnOnce() -> String,
+) -> String {
+    match formatter {
+        Some(formatter) => formatter(field.name(), value),
+        None => default(),
+    }
+}
+
+/// Error events with this field set to `true` are buffered, but don't trigger a report.
+const SKIP_FIELD: &str = "dontpanic.skip";
+
+/// Looks for the [`SKIP_FIELD`] flag, by its raw value so a [`Builder::field_formatter`](crate::Builder::field_formatter) can't hide it.
+#[derive(Default)]
+struct SkipVisitor {
+    skip: bool,
+}
+
+impl Visit for SkipVisitor {
+    fn record_debug(&mut self, _field: &Field, _value: &dyn fmt::Debug) {}
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        if field.name() == SKIP_FIELD {
+            self.skip = value;
         }
     }
 }
 
+/// Fields of a span, stored in its extensions.
+struct SpanFields(HashMap<String, String>);
+
 /// A tracing [`Layer`] implementation that records tracing events.
 ///
+/// Reports triggered by error events include the names and fields of the spans the event occurred in.
+/// This requires a subscriber that supports span lookups, such as [`tracing_subscriber::registry`].
+///
+/// Error events with a `dontpanic.skip = true` field are stored in the log buffer, but don't send a report. Useful for errors
+/// that are expected and handled:
+///
+/// ```no_run
+/// tracing::error!(dontpanic.skip = true, "Payment declined, asking the user to retry");
+/// ```
+///
 /// This can be obtained via [`Client::tracing_layer`](crate::Client::tracing_layer)
 pub struct TracingLayer {
-    pub(crate) tx: RingSender<LogEvent>,
-    pub(crate) rx: RingReceiver<LogEvent>,
+    pub(crate) tx: LogSender,
+    pub(crate) logs: Arc<LogBuffer>,
     pub(crate) config: Config,
 }
 
-impl<S: Subscriber> Layer<S> for TracingLayer {
-    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
-        if !self.config.is_enabled.load(Ordering::Relaxed) {
+impl<S> Layer<S> for TracingLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &span::Attributes<'_>, id: &span::Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+
+        let mut fields = HashMap::new();
+        attrs.record(&mut FieldVisitor {
+            fields: &mut fields,
+            formatter: self.config.field_formatter.as_deref(),
+        });
+
+        span.extensions_mut().insert(SpanFields(fields));
+    }
+
+    fn on_record(&self, id: &span::Id, values: &span::Record<'_>, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+
+        let mut extensions = span.extensions_mut();
+
+        if let Some(SpanFields(fields)) = extensions.get_mut::<SpanFields>() {
+            values.record(&mut FieldVisitor {
+                fields,
+                formatter: self.config.field_formatter.as_deref(),
+            });
+        }
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        if !self.config.reports_enabled() {
             return;
         }
 
@@ -54,13 +205,34 @@ impl<S: Subscriber> Layer<S> for TracingLayer {
             return;
         }
 
-        let _ = self.tx.send(LogEvent::from(event));
+        let level = Severity::from(*metadata.level()) as u8;
+        let captures = self.config.captures(level, metadata.target());
+        // Reporting an error logged while sending a report could trigger another one, indefinitely
+        let triggers = !SENDING.get() && self.config.triggers_report(level, metadata.target());
+
+        if !captures && !triggers {
+            return;
+        }
+
+        let formatter = self.config.field_formatter.as_deref();
+        let message = event_message(event, formatter);
 
-        if *metadata.level() != Level::ERROR || !self.config.report_on_log_errors {
+        if captures {
+            let event_log = log_entry(event, message.clone(), formatter);
+            log_snapshot::record(&event_log);
+            self.tx.send(event_log);
+        }
+
+        if !triggers {
             return;
         }
 
-        let message = event_message(event);
+        let mut skip = SkipVisitor::default();
+        event.record(&mut skip);
+
+        if skip.skip {
+            return;
+        }
 
         let loc = if let (Some(file), Some(line)) = (metadata.file(), metadata.line()) {
             Some(ReportLocation {
@@ -72,43 +244,52 @@ impl<S: Subscriber> Layer<S> for TracingLayer {
             None
         };
 
-        dbg!(&message);
-
-        send_report(&self.config, message, loc, &self.rx)
+        if let Some(mut report) =
+            create_sampled_report(&self.config, message, loc, Logs::Buffer(&self.logs))
+        {
+            report.spans = event_spans(event, &ctx);
+            worker::enqueue(&self.config, report);
+        }
     }
 }
 
-impl From<&Event<'_>> for LogEvent {
-    fn from(event: &Event) -> Self {
-        let metadata = event.metadata();
+fn log_entry(event: &Event<'_>, message: String, formatter: Option<&FieldFormatter>) -> LogEntry {
+    let metadata = event.metadata();
 
-        Self {
-            timestamp: SystemTime::now()
-                .duration_since(UNIX_EPOCH)
-                .map(|d| d.as_secs())
-                .unwrap_or_default(),
-            level: match *metadata.level() {
-                Level::ERROR => 1,
-                Level::WARN => 2,
-                Level::INFO => 3,
-                Level::DEBUG => 4,
-                Level::TRACE => 5,
-            },
-            message: event_message(event),
-            module: Some(metadata.target().to_string()),
-            file: metadata.file().map(String::from),
-            line: metadata.line(),
-        }
+    let timestamp = now_millis();
+
+    LogEntry {
+        timestamp: timestamp.unwrap_or_default(),
+        clock_skew: timestamp.is_none(),
+        level: Severity::from(*metadata.level()) as u8,
+        message,
+        module: Some(metadata.target().to_string()),
+        file: metadata.file().map(String::from),
+        line: metadata.line(),
+        fields: event_fields(event, formatter),
     }
 }
 
-fn event_message(event: &Event<'_>) -> String {
+fn event_fields(event: &Event<'_>, formatter: Option<&FieldFormatter>) -> HashMap<String, String> {
+    let mut fields = HashMap::new();
+
+    event.record(&mut FieldVisitor {
+        fields: &mut fields,
+        formatter,
+    });
+
+    fields.remove("message");
+    fields
+}
+
+fn event_message(event: &Event<'_>, formatter: Option<&FieldFormatter>) -> String {
     let metadata = event.metadata();
 
     let mut message = String::new();
 
     event.record(&mut MessageVisitor {
         message: &mut message,
+        formatter,
     });
 
     if message.is_empty() {
@@ -122,3 +303,26 @@ fn event_message(event: &Event<'_>) -> String {
 
     message
 }
+
+/// Collects the spans an event occurred in, outermost first.
+fn event_spans<S>(event: &Event<'_>, ctx: &Context<'_, S>) -> Vec<SpanEntry>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    let Some(scope) = ctx.event_scope(event) else {
+        return Vec::new();
+    };
+
+    scope
+        .from_root()
+        .map(|span| SpanEntry {
+            name: span.name().to_string(),
+            target: span.metadata().target().to_string(),
+            fields: span
+                .extensions()
+                .get::<SpanFields>()
+                .map(|SpanFields(fields)| fields.clone())
+                .unwrap_or_default(),
+        })
+        .collect()
+}
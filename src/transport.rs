@@ -0,0 +1,73 @@
+use ureq::Agent;
+
+use super::TransportError;
+
+/// Sends encoded reports to the backend server.
+///
+/// The default transport uses a [ureq](https://docs.rs/ureq/latest/ureq/) agent configured with [`Builder::timeout`](crate::Builder::timeout),
+/// [`Builder::connect_timeout`](crate::Builder::connect_timeout) and [`Builder::proxy`](crate::Builder::proxy). Implement this trait and pass it
+/// to [`Builder::transport`](crate::Builder::transport) to send reports with a different HTTP client, or anywhere else.
+///
+/// Retries and offline storage are handled by dontpanic, implementations should make a single attempt.
+pub trait Transport: Send + Sync {
+    /// Sends `body` as a POST request to `url`. `body` is compressed according to [`Builder::compression`](crate::Builder::compression),
+    /// and `headers` contain the [`User-Agent`](crate::Builder::user_agent), the matching `Content-Type` and `Content-Encoding` headers, as well as the ones added with [`Builder::header`](crate::Builder::header).
+    ///
+    /// Returns the status code of the successful response, which is passed to [`Builder::on_send_success`](crate::Builder::on_send_success).
+    fn send(&self, url: &str, body: &[u8], headers: &Headers) -> Result<u16, TransportError>;
+}
+
+/// HTTP headers sent with a report, in the order they were added.
+#[derive(Clone, Debug, Default)]
+pub struct Headers(Vec<(String, String)>);
+
+impl Headers {
+    pub(crate) fn new(headers: Vec<(String, String)>) -> Self {
+        Self(headers)
+    }
+
+    /// Iterates over all header names and values
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.0
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_str()))
+    }
+
+    /// Returns the value of the last header named `name`, compared case insensitively
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.iter()
+            .filter(|(header, _)| header.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value)
+            .last()
+    }
+}
+
+/// The default [`Transport`], sending reports with ureq.
+pub struct UreqTransport {
+    agent: Agent,
+}
+
+impl UreqTransport {
+    pub fn new(agent: Agent) -> Self {
+        Self { agent }
+    }
+}
+
+impl Transport for UreqTransport {
+    fn send(&self, url: &str, body: &[u8], headers: &Headers) -> Result<u16, TransportError> {
+        let request = headers
+            .iter()
+            .fold(self.agent.post(url), |request, (name, value)| {
+                request.set(name, value)
+            });
+
+        match request.send_bytes(body) {
+            Ok(response) => Ok(response.status()),
+            Err(ureq::Error::Status(code, response)) => Err(TransportError::Status {
+                code,
+                response: response.into_string().ok(),
+            }),
+            Err(ureq::Error::Transport(e)) => Err(TransportError::Other(e.to_string())),
+        }
+    }
+}
@@ -0,0 +1,111 @@
+use super::{FieldNaming, Report, ReportData};
+
+/// Appended to a backtrace that was cut short.
+const TRUNCATED_MARKER: &str = "\n...";
+
+/// Shrinks a report until its JSON encoding fits in `max_bytes`, see [`Builder::max_payload_bytes`](crate::Builder::max_payload_bytes).
+///
+/// The oldest log messages are dropped first, then the oldest lines of standard error, then the structured frames and the [extra](ReportData::extra) data, then the end of the backtrace is cut off. If the report still doesn't fit,
+/// the backtrace is left out entirely and the report is sent as small as it gets.
+pub fn fit(
+    api_key: &str,
+    naming: FieldNaming,
+    mut data: ReportData,
+    max_bytes: usize,
+) -> ReportData {
+    if encoded_len(api_key, naming, &data) <= max_bytes {
+        return data;
+    }
+
+    data.truncated = true;
+
+    let mut excess = encoded_len(api_key, naming, &data).saturating_sub(max_bytes);
+
+    let mut drop_count = 0;
+    let mut dropped_bytes = 0;
+
+    for entry in &data.log {
+        if dropped_bytes >= excess {
+            break;
+        }
+
+        // Plus one for the separating comma
+        dropped_bytes += serde_json::to_vec(entry).map_or(0, |json| json.len()) + 1;
+        drop_count += 1;
+    }
+
+    data.log.drain(..drop_count);
+    excess = excess.saturating_sub(dropped_bytes);
+
+    if excess == 0 {
+        return data;
+    }
+
+    // Standard error lines are unstructured log output, dropped the same way
+    let mut drop_count = 0;
+    let mut dropped_bytes = 0;
+
+    for line in &data.stderr_tail {
+        if dropped_bytes >= excess {
+            break;
+        }
+
+        dropped_bytes += serde_json::to_vec(line).map_or(0, |json| json.len()) + 1;
+        drop_count += 1;
+    }
+
+    data.stderr_tail.drain(..drop_count);
+    excess = excess.saturating_sub(dropped_bytes);
+
+    if excess == 0 {
+        return data;
+    }
+
+    // Frames repeat what's in the backtrace, so they go first
+    if !data.frames.is_empty() {
+        data.frames.clear();
+        excess = encoded_len(api_key, naming, &data).saturating_sub(max_bytes);
+
+        if excess == 0 {
+            return data;
+        }
+    }
+
+    if data.extra.is_some() {
+        data.extra = None;
+        excess = encoded_len(api_key, naming, &data).saturating_sub(max_bytes);
+
+        if excess == 0 {
+            return data;
+        }
+    }
+
+    if let Some(backtrace) = &mut data.backtrace {
+        let keep = backtrace
+            .len()
+            .saturating_sub(excess + TRUNCATED_MARKER.len());
+
+        if keep == 0 {
+            data.backtrace = None;
+        } else {
+            let keep = (0..=keep)
+                .rev()
+                .find(|&i| backtrace.is_char_boundary(i))
+                .unwrap_or_default();
+
+            backtrace.truncate(keep);
+            backtrace.push_str(TRUNCATED_MARKER);
+        }
+    }
+
+    // Escaping may have made the estimate too small
+    if encoded_len(api_key, naming, &data) > max_bytes {
+        data.backtrace = None;
+    }
+
+    data
+}
+
+fn encoded_len(api_key: &str, naming: FieldNaming, data: &ReportData) -> usize {
+    serde_json::to_vec(&Report::new(api_key, data).to_json(naming)).map_or(0, |json| json.len())
+}
@@ -0,0 +1,104 @@
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::os::unix::net::UnixStream;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use super::{Headers, Transport, TransportError};
+
+/// Sends reports as HTTP/1.1 requests over a Unix domain socket, see [`Builder::unix_socket`](crate::Builder::unix_socket).
+pub struct UnixSocketTransport {
+    path: PathBuf,
+    timeout: Duration,
+}
+
+impl UnixSocketTransport {
+    pub fn new(path: PathBuf, timeout: Duration) -> Self {
+        Self { path, timeout }
+    }
+
+    fn request(&self, url: &str, body: &[u8], headers: &Headers) -> io::Result<(u16, String)> {
+        // The url only provides the Host header and request path, the socket decides where the request goes
+        let url = url.split_once("://").map_or(url, |(_, rest)| rest);
+        let (host, path) = match url.find('/') {
+            Some(index) => url.split_at(index),
+            None => (url, "/"),
+        };
+
+        let mut stream = UnixStream::connect(&self.path)?;
+        stream.set_read_timeout(Some(self.timeout))?;
+        stream.set_write_timeout(Some(self.timeout))?;
+
+        let mut request = format!("POST {} HTTP/1.1\r\nHost: {}\r\n", path, host);
+
+        for (name, value) in headers.iter() {
+            request.push_str(&format!("{}: {}\r\n", name, value));
+        }
+
+        request.push_str(&format!(
+            "Content-Length: {}\r\nConnection: close\r\n\r\n",
+            body.len()
+        ));
+
+        stream.write_all(request.as_bytes())?;
+        stream.write_all(body)?;
+        stream.flush()?;
+
+        let mut reader = BufReader::new(stream);
+
+        let mut status_line = String::new();
+        reader.read_line(&mut status_line)?;
+
+        let code = status_line
+            .split_whitespace()
+            .nth(1)
+            .and_then(|code| code.parse().ok())
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("invalid HTTP status line {:?}", status_line.trim_end()),
+                )
+            })?;
+
+        let mut content_length = None;
+
+        loop {
+            let mut line = String::new();
+
+            if reader.read_line(&mut line)? == 0 || line.trim().is_empty() {
+                break;
+            }
+
+            if let Some((name, value)) = line.split_once(':') {
+                if name.eq_ignore_ascii_case("content-length") {
+                    content_length = value.trim().parse::<u64>().ok();
+                }
+            }
+        }
+
+        let mut response = Vec::new();
+
+        match content_length {
+            Some(length) => reader.take(length).read_to_end(&mut response)?,
+            None => reader.read_to_end(&mut response)?,
+        };
+
+        Ok((code, String::from_utf8_lossy(&response).into_owned()))
+    }
+}
+
+impl Transport for UnixSocketTransport {
+    fn send(&self, url: &str, body: &[u8], headers: &Headers) -> Result<u16, TransportError> {
+        match self.request(url, body, headers) {
+            Ok((code, _)) if code < 400 => Ok(code),
+            Ok((code, response)) => Err(TransportError::Status {
+                code,
+                response: Some(response),
+            }),
+            Err(e) => Err(TransportError::Other(format!(
+                "{}: {}",
+                self.path.display(),
+                e
+            ))),
+        }
+    }
+}
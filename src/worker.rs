@@ -0,0 +1,270 @@
+use std::sync::mpsc::{sync_channel, Receiver, RecvTimeoutError, SyncSender, TrySendError};
+use std::sync::{Condvar, Mutex, PoisonError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use super::{
+    send_batch, send_report, send_report_guarded, Config, Metric, ReportData, MAX_SYNC_RETRIES,
+};
+
+/// Limits of a batch of reports sent in a single request, see [`Builder::batch`](crate::Builder::batch).
+#[derive(Clone, Copy, Debug)]
+pub struct Batch {
+    pub max_count: usize,
+    pub max_delay: Duration,
+}
+
+/// Number of reports handed over to the worker that are not yet sent.
+#[derive(Debug, Default)]
+pub struct Pending {
+    count: Mutex<usize>,
+    drained: Condvar,
+}
+
+impl Pending {
+    fn add(&self) {
+        *self.count.lock().unwrap_or_else(PoisonError::into_inner) += 1;
+    }
+
+    #[cfg(feature = "tokio")]
+    fn try_add(&self, max: usize) -> bool {
+        let mut count = self.count.lock().unwrap_or_else(PoisonError::into_inner);
+
+        if *count >= max {
+            return false;
+        }
+
+        *count += 1;
+        true
+    }
+
+    fn done(&self) {
+        let mut count = self.count.lock().unwrap_or_else(PoisonError::into_inner);
+        *count = count.saturating_sub(1);
+
+        if *count == 0 {
+            self.drained.notify_all();
+        }
+    }
+
+    /// Blocks until there are no pending reports. Returns `false` if the timeout elapsed first.
+    pub fn wait(&self, timeout: Option<Duration>) -> bool {
+        let deadline = timeout.map(|timeout| Instant::now() + timeout);
+        let mut count = self.count.lock().unwrap_or_else(PoisonError::into_inner);
+
+        while *count > 0 {
+            count = match deadline {
+                Some(deadline) => {
+                    let Some(remaining) = deadline.checked_duration_since(Instant::now()) else {
+                        return false;
+                    };
+
+                    self.drained
+                        .wait_timeout(count, remaining)
+                        .unwrap_or_else(PoisonError::into_inner)
+                        .0
+                }
+                None => self
+                    .drained
+                    .wait(count)
+                    .unwrap_or_else(PoisonError::into_inner),
+            };
+        }
+
+        true
+    }
+}
+
+/// Limits the number of requests sent at the same time, see [`Builder::max_in_flight`](crate::Builder::max_in_flight).
+#[derive(Debug)]
+pub struct InFlight {
+    available: Mutex<usize>,
+    released: Condvar,
+}
+
+impl InFlight {
+    pub fn new(max: usize) -> Self {
+        Self {
+            available: Mutex::new(max),
+            released: Condvar::new(),
+        }
+    }
+
+    /// Blocks until a request may be sent, or returns `None` if none finished within `timeout`. The slot is freed when the permit is dropped.
+    pub fn acquire(&self, timeout: Duration) -> Option<InFlightPermit<'_>> {
+        let deadline = Instant::now() + timeout;
+        let mut available = self
+            .available
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+
+        while *available == 0 {
+            let remaining = deadline.checked_duration_since(Instant::now())?;
+
+            available = self
+                .released
+                .wait_timeout(available, remaining)
+                .unwrap_or_else(PoisonError::into_inner)
+                .0;
+        }
+
+        *available -= 1;
+
+        Some(InFlightPermit { in_flight: self })
+    }
+}
+
+/// A request counted against [`InFlight`], returned by [`InFlight::acquire`].
+pub struct InFlightPermit<'a> {
+    in_flight: &'a InFlight,
+}
+
+impl Drop for InFlightPermit<'_> {
+    fn drop(&mut self) {
+        *self
+            .in_flight
+            .available
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner) += 1;
+
+        self.in_flight.released.notify_one();
+    }
+}
+
+/// Spawns the background thread that delivers queued reports and returns the sending half of its queue.
+pub fn spawn(config: &Config, capacity: usize) -> SyncSender<ReportData> {
+    let (tx, rx) = sync_channel(capacity);
+
+    let config = config.clone();
+
+    let res = thread::Builder::new()
+        .name("dontpanic-worker".into())
+        .spawn(move || run(config, rx));
+
+    if let Err(e) = res {
+        eprintln!("Failed to spawn dontpanic worker thread. Error: {:?}", e);
+    }
+
+    tx
+}
+
+fn run(config: Config, rx: Receiver<ReportData>) {
+    if let Some(batch) = config.batch {
+        return run_batched(config, rx, batch);
+    }
+
+    while let Ok(report) = rx.recv() {
+        send_report_guarded(&config, || send_report(&config, report, config.max_retries));
+        config.pending.done();
+    }
+}
+
+fn run_batched(config: Config, rx: Receiver<ReportData>, batch: Batch) {
+    while let Ok(report) = rx.recv() {
+        let deadline = Instant::now() + batch.max_delay;
+        let mut reports = vec![report];
+
+        while reports.len() < batch.max_count {
+            let Some(remaining) = deadline.checked_duration_since(Instant::now()) else {
+                break;
+            };
+
+            match rx.recv_timeout(remaining) {
+                Ok(report) => reports.push(report),
+                Err(RecvTimeoutError::Timeout | RecvTimeoutError::Disconnected) => break,
+            }
+        }
+
+        let count = reports.len();
+        send_report_guarded(&config, || send_batch(&config, reports, config.max_retries));
+
+        for _ in 0..count {
+            config.pending.done();
+        }
+    }
+}
+
+/// Hands a report over to the background worker without blocking. If the queue is full, the report is dropped.
+pub fn enqueue(config: &Config, report: ReportData) {
+    #[cfg(feature = "tokio")]
+    if let Some(runtime) = config.runtime.as_ref().filter(|_| config.batch.is_none()) {
+        spawn_blocking(config, runtime, report);
+        return;
+    }
+
+    enqueue_worker(config, report);
+}
+
+/// Queues a report for the worker thread, or sends it right away if the thread is gone.
+fn enqueue_worker(config: &Config, report: ReportData) {
+    let Some(tx) = &config.report_tx else {
+        send_report_guarded(config, || {
+            send_report(config, report, config.max_retries.min(MAX_SYNC_RETRIES))
+        });
+        return;
+    };
+
+    config.pending.add();
+
+    match tx.try_send(report) {
+        Ok(()) => (),
+        Err(TrySendError::Full(_)) => {
+            config.pending.done();
+            config.metric(Metric::QueueFull);
+            eprintln!(
+                "Report queue is full, dropping report to {}",
+                config.ingress_url
+            );
+        }
+        Err(TrySendError::Disconnected(report)) => {
+            config.pending.done();
+            send_report_guarded(config, || {
+                send_report(config, report, config.max_retries.min(MAX_SYNC_RETRIES))
+            });
+        }
+    }
+}
+
+/// Sends the report on the blocking thread pool of a tokio runtime, instead of the worker thread.
+#[cfg(feature = "tokio")]
+fn spawn_blocking(config: &Config, runtime: &tokio::runtime::Handle, report: ReportData) {
+    if !config.pending.try_add(config.queue_capacity) {
+        config.metric(Metric::QueueFull);
+        eprintln!(
+            "Report queue is full, dropping report to {}",
+            config.ingress_url
+        );
+        return;
+    }
+
+    let mut task = BlockingTask {
+        config: config.clone(),
+        report: Some(report),
+    };
+
+    runtime.spawn_blocking(move || {
+        if let Some(report) = task.report.take() {
+            let config = &task.config;
+            send_report_guarded(config, || send_report(config, report, config.max_retries));
+        }
+    });
+}
+
+/// A report offloaded to the tokio blocking pool. Tokio drops tasks that didn't start before the runtime shut down, so the
+/// pending count is released on drop, and a report that wasn't sent yet is handed to the worker thread instead of being lost.
+#[cfg(feature = "tokio")]
+struct BlockingTask {
+    config: Config,
+    report: Option<ReportData>,
+}
+
+#[cfg(feature = "tokio")]
+impl Drop for BlockingTask {
+    fn drop(&mut self) {
+        if let Some(report) = self.report.take() {
+            enqueue_worker(&self.config, report);
+        }
+
+        self.config.pending.done();
+    }
+}